@@ -0,0 +1,6 @@
+ALTER TABLE following
+    DROP COLUMN mention_here;
+ALTER TABLE following
+    DROP COLUMN mention_user_ids;
+ALTER TABLE following
+    DROP COLUMN mention_role_id;
@@ -0,0 +1,6 @@
+ALTER TABLE following
+    ADD COLUMN mention_role_id VARCHAR(20);
+ALTER TABLE following
+    ADD COLUMN mention_user_ids JSONB NOT NULL DEFAULT '[]'::JSONB;
+ALTER TABLE following
+    ADD COLUMN mention_here BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_digest_queue_channel_id;
+
+DROP TABLE IF EXISTS digest_queue;
+DROP TABLE IF EXISTS channel_settings;
@@ -0,0 +1,23 @@
+CREATE TABLE channel_settings
+(
+    channel_id     VARCHAR(255) PRIMARY KEY,
+    delivery_mode  VARCHAR(16)  NOT NULL DEFAULT 'immediate',
+    digest_time    TIME         NOT NULL DEFAULT '09:00',
+    digest_weekday INT          NOT NULL DEFAULT 1,
+    timezone       VARCHAR(64)  NOT NULL DEFAULT 'UTC',
+    last_digest_at TIMESTAMP
+);
+
+CREATE TABLE digest_queue
+(
+    id         SERIAL PRIMARY KEY,
+    channel_id VARCHAR(255) NOT NULL,
+    user_name  VARCHAR(255) NOT NULL,
+    gmaps_id   VARCHAR(255) NOT NULL,
+    place_name VARCHAR(255) NOT NULL,
+    stars      INT          NOT NULL,
+    link       TEXT,
+    queued_at  TIMESTAMP    NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_digest_queue_channel_id ON digest_queue (channel_id);
@@ -0,0 +1,4 @@
+ALTER TABLE following
+    DROP COLUMN thread_id;
+ALTER TABLE following
+    DROP COLUMN thread_per_user;
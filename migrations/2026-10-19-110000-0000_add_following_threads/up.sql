@@ -0,0 +1,4 @@
+ALTER TABLE following
+    ADD COLUMN thread_per_user BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE following
+    ADD COLUMN thread_id VARCHAR(20);
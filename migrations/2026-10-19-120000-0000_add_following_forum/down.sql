@@ -0,0 +1,2 @@
+ALTER TABLE following
+    DROP COLUMN forum;
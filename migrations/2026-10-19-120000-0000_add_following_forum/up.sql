@@ -0,0 +1,2 @@
+ALTER TABLE following
+    ADD COLUMN forum BOOLEAN NOT NULL DEFAULT FALSE;
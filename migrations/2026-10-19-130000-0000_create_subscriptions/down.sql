@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_subscriptions_discord_user_id;
+
+DROP TABLE IF EXISTS subscriptions;
@@ -0,0 +1,10 @@
+CREATE TABLE subscriptions
+(
+    id               SERIAL PRIMARY KEY,
+    followed_user_id INTEGER     NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    discord_user_id  VARCHAR(20) NOT NULL,
+    original_text    BOOLEAN     NOT NULL,
+    UNIQUE (followed_user_id, discord_user_id)
+);
+
+CREATE INDEX idx_subscriptions_discord_user_id ON subscriptions (discord_user_id);
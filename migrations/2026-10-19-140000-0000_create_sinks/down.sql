@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_sinks_followed_user_id;
+
+DROP TABLE IF EXISTS sinks;
@@ -0,0 +1,11 @@
+CREATE TABLE sinks
+(
+    id               SERIAL PRIMARY KEY,
+    followed_user_id INTEGER     NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    kind             VARCHAR(16) NOT NULL,
+    target           TEXT        NOT NULL,
+    secret           TEXT,
+    config           JSONB       NOT NULL DEFAULT '{}'::JSONB
+);
+
+CREATE INDEX idx_sinks_followed_user_id ON sinks (followed_user_id);
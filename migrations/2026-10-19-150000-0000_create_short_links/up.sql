@@ -0,0 +1,6 @@
+CREATE TABLE short_links
+(
+    id         SERIAL PRIMARY KEY,
+    url        TEXT      NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
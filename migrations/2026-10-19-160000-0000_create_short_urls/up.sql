@@ -0,0 +1,8 @@
+CREATE TABLE short_urls
+(
+    shortener    TEXT      NOT NULL,
+    original_url TEXT      NOT NULL,
+    short_url    TEXT      NOT NULL,
+    created_at   TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (shortener, original_url)
+);
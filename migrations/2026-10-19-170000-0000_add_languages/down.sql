@@ -0,0 +1,6 @@
+ALTER TABLE subscriptions
+    DROP COLUMN language;
+ALTER TABLE following
+    DROP COLUMN language;
+ALTER TABLE reviews
+    DROP COLUMN language;
@@ -0,0 +1,6 @@
+ALTER TABLE reviews
+    ADD COLUMN language VARCHAR(35);
+ALTER TABLE following
+    ADD COLUMN language VARCHAR(35);
+ALTER TABLE subscriptions
+    ADD COLUMN language VARCHAR(35);
@@ -0,0 +1,569 @@
+use crate::config::get_config;
+use crate::models::{ChannelSettings, DigestEntry, NewDigestEntry, ReviewWithUser};
+use crate::notifier;
+use crate::provider;
+use crate::utility::mention::{self, Mentions};
+use crate::utility::metrics;
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use poise::serenity_prelude as serenity;
+use tracing::Instrument;
+
+/// Discord rejects embed descriptions longer than this.
+const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+/// Discord accepts at most this many embeds per message.
+const EMBEDS_PER_MESSAGE: usize = 10;
+/// Discord rejects messages whose embeds add up to more characters than this.
+const MESSAGE_EMBED_LIMIT: usize = 6000;
+static SINK_DIGEST_PREFIX: &str = "sink:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum DeliveryMode {
+    #[name = "immediate"]
+    Immediate,
+    #[name = "hourly"]
+    Hourly,
+    #[name = "daily"]
+    Daily,
+    #[name = "weekly"]
+    Weekly,
+}
+
+impl DeliveryMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DeliveryMode::Immediate => "immediate",
+            DeliveryMode::Hourly => "hourly",
+            DeliveryMode::Daily => "daily",
+            DeliveryMode::Weekly => "weekly",
+        }
+    }
+
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "immediate" => Some(DeliveryMode::Immediate),
+            "hourly" => Some(DeliveryMode::Hourly),
+            "daily" => Some(DeliveryMode::Daily),
+            "weekly" => Some(DeliveryMode::Weekly),
+            _ => None,
+        }
+    }
+}
+
+pub fn get_delivery_mode(channel_id: &str) -> DeliveryMode {
+    match provider::digest::get_channel_settings(channel_id) {
+        Ok(Some(settings)) => DeliveryMode::parse(settings.delivery_mode.as_str()).unwrap_or_else(|| {
+            tracing::warn!(
+                "Unknown delivery mode '{}' for channel {}, delivering immediately",
+                settings.delivery_mode,
+                channel_id
+            );
+            DeliveryMode::Immediate
+        }),
+        Ok(None) => DeliveryMode::Immediate,
+        Err(e) => {
+            tracing::error!("Failed to get delivery mode for channel {}: {}", channel_id, e);
+            DeliveryMode::Immediate
+        }
+    }
+}
+
+/// Sinks share the channel digest tables under this key.
+pub fn sink_digest_key(sink_id: i32) -> String {
+    format!("{SINK_DIGEST_PREFIX}{sink_id}")
+}
+
+fn parse_sink_digest_key(key: &str) -> Option<i32> {
+    key.strip_prefix(SINK_DIGEST_PREFIX)?.parse().ok()
+}
+
+pub fn queue_review(channel_id: &str, review: &ReviewWithUser) {
+    let entry = NewDigestEntry {
+        channel_id: channel_id.to_string(),
+        user_name: review.user.name.clone(),
+        gmaps_id: review.user.gmaps_id.clone(),
+        place_name: review.review.place_name.clone(),
+        stars: review.review.stars,
+        link: review.review.link_en.clone(),
+    };
+
+    match provider::digest::queue_review(&entry) {
+        Ok(()) => tracing::info!(
+            "Queued review from '{}' for digest in channel '{}'",
+            review.user.gmaps_id,
+            channel_id
+        ),
+        Err(e) => tracing::error!(
+            "Failed to queue review for digest in channel {}: {}",
+            channel_id,
+            e
+        ),
+    }
+}
+
+/// Posts the queued reviews of every channel whose digest is due.
+pub async fn send_due_digests() {
+    let settings = match provider::digest::get_all_channel_settings() {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Failed to load channel settings for digests: {}", e);
+            return;
+        }
+    };
+
+    let now = Utc::now();
+    for channel_settings in settings {
+        if !is_digest_due(&channel_settings, now) {
+            continue;
+        }
+
+        let entries = match provider::digest::get_queued_reviews(channel_settings.channel_id.as_str()) {
+            Ok(e) => e,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load queued reviews for channel {}: {}",
+                    channel_settings.channel_id,
+                    e
+                );
+                continue;
+            }
+        };
+
+        // Immediate channels have no schedule to keep, so they are only flushed when reviews are left over.
+        let immediate = channel_settings.delivery_mode == DeliveryMode::Immediate.as_str();
+        if entries.is_empty() && immediate {
+            continue;
+        }
+
+        if !entries.is_empty() {
+            let span = tracing::info_span!("digest", channel = %channel_settings.channel_id, reviews = entries.len());
+            let sent = send_digest(&channel_settings, &entries).instrument(span).await;
+            metrics::record_notification("digest", sent);
+            if !sent {
+                continue;
+            }
+        }
+        if !immediate
+            && let Err(e) = provider::digest::mark_digest_sent(channel_settings.channel_id.as_str(), now.naive_utc())
+        {
+            tracing::error!("Failed to mark digest as sent for channel {}: {}", channel_settings.channel_id, e);
+        }
+    }
+}
+
+/// Sends the digest and removes the reviews that were delivered from the queue.
+///
+/// Reviews are removed per message, so a digest failing halfway doesn't post its first messages again.
+async fn send_digest(settings: &ChannelSettings, entries: &[DigestEntry]) -> bool {
+    tracing::info!(
+        "Sending digest with {} reviews to channel '{}'",
+        entries.len(),
+        settings.channel_id
+    );
+
+    let mode = DeliveryMode::parse(settings.delivery_mode.as_str()).unwrap_or(DeliveryMode::Immediate);
+    if let Some(sink_id) = parse_sink_digest_key(settings.channel_id.as_str()) {
+        let sent = send_sink_digest(sink_id, mode, entries).await;
+        if sent {
+            clear_queued_reviews(settings, entries.iter().map(|entry| entry.id).collect());
+        }
+        return sent;
+    }
+
+    let followings = match provider::following::get_followings_with_users_in_channel(settings.channel_id.clone()) {
+        Ok(followings) => followings,
+        Err(e) => {
+            tracing::error!("Failed to get follows for channel {}: {}", settings.channel_id, e);
+            return false;
+        }
+    };
+    // Follows in a channel share its webhook, which is recreated when it was deleted, so any of them can post.
+    let Some((following, _)) = followings.first() else {
+        tracing::warn!("No follows left in channel {}, dropping digest", settings.channel_id);
+        clear_queued_reviews(settings, entries.iter().map(|entry| entry.id).collect());
+        return true;
+    };
+    // Ping everyone who asked to be mentioned for one of the users in the digest.
+    let mentions: Vec<Mentions> = followings
+        .iter()
+        .filter(|(_, user)| entries.iter().any(|entry| entry.gmaps_id == user.gmaps_id))
+        .map(|(following, _)| Mentions::from_following(following))
+        .collect();
+
+    let http = serenity::Http::new(get_config().discord_token.as_str());
+    let Some(webhook) = notifier::discord::get_following_webhook(following, &http).await else {
+        return false;
+    };
+
+    let current_user = match http.get_current_user().await {
+        Ok(user) => user,
+        Err(e) => {
+            tracing::error!("Failed to get current bot user: {}", e);
+            return false;
+        }
+    };
+
+    let title = format!("📬 {} review digest", digest_title(mode));
+    let pages = build_digest_pages(entries, get_config().star_text.as_str());
+    let messages = group_digest_pages(pages, title.as_str());
+
+    let timezone = settings.timezone.parse::<Tz>().unwrap_or(Tz::UTC);
+    let post_title = format!("{} – {}", title, Utc::now().with_timezone(&timezone).format("%Y-%m-%d"));
+    // In a forum the first message opens the post, and the rest of the digest is sent into it.
+    let mut thread_id = None;
+    for (index, pages) in messages.into_iter().enumerate() {
+        let embeds: Vec<serenity::CreateEmbed> = pages
+            .iter()
+            .map(|page| {
+                serenity::CreateEmbed::default()
+                    .title(title.clone())
+                    .description(page.description.clone())
+            })
+            .collect();
+        let mut webhook_message = serenity::ExecuteWebhook::new()
+            .username(current_user.name.clone())
+            .avatar_url(current_user.avatar_url().unwrap_or_default())
+            .allowed_mentions(mention::combined_allowed_mentions(&mentions))
+            .embeds(embeds);
+        if index == 0
+            && let Some(content) = mention::combined_content(&mentions)
+        {
+            webhook_message = webhook_message.content(content);
+        }
+        if let Some(thread_id) = thread_id {
+            webhook_message = webhook_message.in_thread(thread_id);
+        } else if following.forum {
+            webhook_message = webhook_message.thread_name(post_title.clone());
+        }
+
+        match webhook.execute(&http, following.forum, webhook_message).await {
+            Ok(message) => {
+                if following.forum && thread_id.is_none() {
+                    let Some(message) = message else {
+                        tracing::error!("Discord did not return the digest post in channel {}", settings.channel_id);
+                        return false;
+                    };
+                    thread_id = Some(message.channel_id);
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to send digest to channel {}: {}", settings.channel_id, e);
+                return false;
+            }
+        }
+        clear_queued_reviews(settings, pages.into_iter().flat_map(|page| page.ids).collect());
+    }
+
+    true
+}
+
+fn clear_queued_reviews(settings: &ChannelSettings, ids: Vec<i32>) {
+    if let Err(e) = provider::digest::delete_queued_reviews(&ids) {
+        tracing::error!("Failed to clear digest queue for channel {}: {}", settings.channel_id, e);
+    }
+}
+
+async fn send_sink_digest(sink_id: i32, mode: DeliveryMode, entries: &[DigestEntry]) -> bool {
+    let sink = match provider::sink::get_sink(sink_id) {
+        Ok(Some(sink)) => sink,
+        Ok(None) => {
+            tracing::warn!("Sink {} no longer exists, dropping digest", sink_id);
+            return true;
+        }
+        Err(e) => {
+            tracing::error!("Failed to load sink {}: {}", sink_id, e);
+            return false;
+        }
+    };
+
+    let sink_notifier = match notifier::from_sink(&sink) {
+        Ok(n) => n,
+        Err(e) => {
+            tracing::error!("Failed to build notifier for sink {}: {}", sink_id, e);
+            return false;
+        }
+    };
+
+    let title = format!("{} review digest", digest_title(mode));
+    match sink_notifier.notify_digest(title.as_str(), entries).await {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::error!("Failed to send digest to '{}': {}", sink_notifier.target(), e);
+            false
+        }
+    }
+}
+
+fn digest_title(mode: DeliveryMode) -> &'static str {
+    match mode {
+        DeliveryMode::Immediate => "Pending",
+        DeliveryMode::Hourly => "Hourly",
+        DeliveryMode::Daily => "Daily",
+        DeliveryMode::Weekly => "Weekly",
+    }
+}
+
+/// Reviews shown in one digest embed, with the IDs of their queue entries.
+#[derive(Debug, Default)]
+struct DigestPage {
+    description: String,
+    ids: Vec<i32>,
+}
+
+/// Formats the queued reviews as one line each, split into embed-sized pages.
+fn build_digest_pages(entries: &[DigestEntry], star_text: &str) -> Vec<DigestPage> {
+    let mut pages = Vec::new();
+    let mut current = DigestPage::default();
+
+    for entry in entries {
+        let place = match &entry.link {
+            Some(link) => format!("[{}]({})", entry.place_name, link),
+            None => entry.place_name.clone(),
+        };
+        let line = format!(
+            "**{}** reviewed {} {}\n",
+            entry.user_name,
+            place,
+            star_text.repeat(entry.stars.max(0).try_into().unwrap_or_default())
+        );
+
+        if !current.description.is_empty()
+            && current.description.chars().count() + line.chars().count() > EMBED_DESCRIPTION_LIMIT
+        {
+            pages.push(std::mem::take(&mut current));
+        }
+        current.description.push_str(line.as_str());
+        current.ids.push(entry.id);
+    }
+
+    if !current.description.is_empty() {
+        pages.push(current);
+    }
+    pages
+}
+
+/// Groups pages titled `title` into messages within Discord's limits on embeds and embed characters per message.
+fn group_digest_pages(pages: Vec<DigestPage>, title: &str) -> Vec<Vec<DigestPage>> {
+    let mut messages = Vec::new();
+    let mut current: Vec<DigestPage> = Vec::new();
+    let mut length = 0;
+
+    for page in pages {
+        let page_length = title.chars().count() + page.description.chars().count();
+        if !current.is_empty() && (current.len() == EMBEDS_PER_MESSAGE || length + page_length > MESSAGE_EMBED_LIMIT) {
+            messages.push(std::mem::take(&mut current));
+            length = 0;
+        }
+        length += page_length;
+        current.push(page);
+    }
+
+    if !current.is_empty() {
+        messages.push(current);
+    }
+    messages
+}
+
+/// A channel is due once a scheduled digest slot has passed since the last digest was sent.
+///
+/// Immediate channels are always due so reviews queued before switching modes get flushed.
+fn is_digest_due(settings: &ChannelSettings, now: DateTime<Utc>) -> bool {
+    let Some(mode) = DeliveryMode::parse(settings.delivery_mode.as_str()) else {
+        return false;
+    };
+    if mode == DeliveryMode::Immediate {
+        return true;
+    }
+
+    let Ok(timezone) = settings.timezone.parse::<Tz>() else {
+        tracing::warn!(
+            "Invalid timezone '{}' for channel {}",
+            settings.timezone,
+            settings.channel_id
+        );
+        return false;
+    };
+
+    let Some(slot) = last_digest_slot(mode, settings, timezone, now) else {
+        return false;
+    };
+    settings
+        .last_digest_at
+        .is_none_or(|last_sent| last_sent.and_utc() < slot)
+}
+
+fn last_digest_slot(
+    mode: DeliveryMode,
+    settings: &ChannelSettings,
+    timezone: Tz,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let local_now = now.with_timezone(&timezone).naive_local();
+    let time = settings.digest_time;
+
+    let slot = match mode {
+        DeliveryMode::Immediate => return Some(now),
+        DeliveryMode::Hourly => {
+            let slot = local_now.date().and_hms_opt(local_now.hour(), time.minute(), 0)?;
+            if slot > local_now { slot - Duration::hours(1) } else { slot }
+        }
+        DeliveryMode::Daily => {
+            let slot = local_now.date().and_time(time);
+            if slot > local_now { slot - Duration::days(1) } else { slot }
+        }
+        DeliveryMode::Weekly => {
+            let target = i64::from(settings.digest_weekday.clamp(1, 7));
+            let today = i64::from(local_now.weekday().number_from_monday());
+            let slot = (local_now.date() - Duration::days((today - target).rem_euclid(7))).and_time(time);
+            if slot > local_now { slot - Duration::weeks(1) } else { slot }
+        }
+    };
+
+    resolve_local_time(timezone, slot)
+}
+
+/// Maps a local wall-clock time to UTC, moving past DST gaps.
+fn resolve_local_time(timezone: Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    timezone
+        .from_local_datetime(&local)
+        .earliest()
+        .or_else(|| timezone.from_local_datetime(&(local + Duration::hours(1))).earliest())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_digest_pages, group_digest_pages, is_digest_due, DeliveryMode, EMBEDS_PER_MESSAGE,
+        EMBED_DESCRIPTION_LIMIT, MESSAGE_EMBED_LIMIT,
+    };
+    use crate::models::{ChannelSettings, DigestEntry};
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+    fn settings(mode: &str, time: &str, weekday: i32, timezone: &str, last: Option<NaiveDateTime>) -> ChannelSettings {
+        ChannelSettings {
+            channel_id: "1".to_string(),
+            delivery_mode: mode.to_string(),
+            digest_time: NaiveTime::parse_from_str(time, "%H:%M").unwrap(),
+            digest_weekday: weekday,
+            timezone: timezone.to_string(),
+            last_digest_at: last,
+        }
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap()
+    }
+
+    fn entry(user_name: &str, place_name: &str, stars: i32, link: Option<&str>) -> DigestEntry {
+        DigestEntry {
+            id: 1,
+            channel_id: "1".to_string(),
+            user_name: user_name.to_string(),
+            gmaps_id: "gmaps".to_string(),
+            place_name: place_name.to_string(),
+            stars,
+            link: link.map(str::to_string),
+            queued_at: Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn delivery_mode_round_trips_through_strings() {
+        for mode in [DeliveryMode::Immediate, DeliveryMode::Hourly, DeliveryMode::Daily, DeliveryMode::Weekly] {
+            assert_eq!(DeliveryMode::parse(mode.as_str()), Some(mode));
+        }
+        assert_eq!(DeliveryMode::parse("monthly"), None);
+    }
+
+    #[test]
+    fn daily_digest_is_due_after_configured_time() {
+        let s = settings("daily", "09:00", 1, "UTC", Some(at(2026, 1, 14, 9, 0)));
+        assert!(!is_digest_due(&s, Utc.from_utc_datetime(&at(2026, 1, 15, 8, 59))));
+        assert!(is_digest_due(&s, Utc.from_utc_datetime(&at(2026, 1, 15, 9, 0))));
+    }
+
+    #[test]
+    fn daily_digest_is_not_due_twice_for_same_slot() {
+        let s = settings("daily", "09:00", 1, "UTC", Some(at(2026, 1, 15, 9, 1)));
+        assert!(!is_digest_due(&s, Utc.from_utc_datetime(&at(2026, 1, 15, 23, 0))));
+    }
+
+    #[test]
+    fn daily_digest_respects_timezone() {
+        // 09:00 in Amsterdam is 08:00 UTC in winter.
+        let s = settings("daily", "09:00", 1, "Europe/Amsterdam", Some(at(2026, 1, 14, 8, 0)));
+        assert!(!is_digest_due(&s, Utc.from_utc_datetime(&at(2026, 1, 15, 7, 59))));
+        assert!(is_digest_due(&s, Utc.from_utc_datetime(&at(2026, 1, 15, 8, 0))));
+    }
+
+    #[test]
+    fn hourly_digest_uses_configured_minute() {
+        let s = settings("hourly", "00:30", 1, "UTC", Some(at(2026, 1, 15, 9, 30)));
+        assert!(!is_digest_due(&s, Utc.from_utc_datetime(&at(2026, 1, 15, 10, 29))));
+        assert!(is_digest_due(&s, Utc.from_utc_datetime(&at(2026, 1, 15, 10, 30))));
+    }
+
+    #[test]
+    fn weekly_digest_waits_for_configured_weekday() {
+        // 2026-01-12 is a Monday.
+        let s = settings("weekly", "09:00", 1, "UTC", Some(at(2026, 1, 5, 9, 0)));
+        assert!(is_digest_due(&s, Utc.from_utc_datetime(&at(2026, 1, 12, 9, 0))));
+
+        let s = settings("weekly", "09:00", 1, "UTC", Some(at(2026, 1, 12, 9, 0)));
+        assert!(!is_digest_due(&s, Utc.from_utc_datetime(&at(2026, 1, 18, 23, 0))));
+        assert!(is_digest_due(&s, Utc.from_utc_datetime(&at(2026, 1, 19, 9, 0))));
+    }
+
+    #[test]
+    fn invalid_timezone_is_never_due() {
+        let s = settings("daily", "09:00", 1, "Mars/Olympus", None);
+        assert!(!is_digest_due(&s, Utc::now()));
+    }
+
+    #[test]
+    fn build_digest_pages_lists_each_review() {
+        let entries = vec![
+            entry("Alice", "Cafe", 5, Some("https://example.com/1")),
+            entry("Bob", "Bar", 2, None),
+        ];
+        let pages = build_digest_pages(&entries, "*");
+        assert_eq!(pages.len(), 1);
+        assert_eq!(
+            pages[0].description,
+            "**Alice** reviewed [Cafe](https://example.com/1) *****\n**Bob** reviewed Bar **\n"
+        );
+    }
+
+    #[test]
+    fn build_digest_pages_splits_long_digests() {
+        let long_place = "P".repeat(200);
+        let entries: Vec<DigestEntry> = (0..50).map(|_| entry("Alice", long_place.as_str(), 5, None)).collect();
+        let pages = build_digest_pages(&entries, "*");
+        assert!(pages.len() > 1);
+        assert!(pages.iter().all(|page| page.description.chars().count() <= EMBED_DESCRIPTION_LIMIT));
+    }
+
+    #[test]
+    fn long_digest_messages_stay_within_discord_limits() {
+        let title = "📬 Weekly review digest";
+        let long_place = "P".repeat(300);
+        let entries: Vec<DigestEntry> = (0..500)
+            .map(|id| DigestEntry { id, ..entry("Alice", long_place.as_str(), 5, Some("https://example.com/1")) })
+            .collect();
+
+        let messages = group_digest_pages(build_digest_pages(&entries, "⭐"), title);
+
+        assert!(messages.len() > 1);
+        for pages in &messages {
+            let length: usize = pages
+                .iter()
+                .map(|page| title.chars().count() + page.description.chars().count())
+                .sum();
+            assert!(length <= MESSAGE_EMBED_LIMIT, "message has {} embed characters", length);
+            assert!(pages.len() <= EMBEDS_PER_MESSAGE);
+        }
+        let ids: Vec<i32> = messages.into_iter().flatten().flat_map(|page| page.ids).collect();
+        assert_eq!(ids, (0..500).collect::<Vec<_>>());
+    }
+}
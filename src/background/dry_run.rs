@@ -0,0 +1,221 @@
+use crate::background::digest::{self, DeliveryMode};
+use crate::background::worker;
+use crate::models::{ReviewWithUser, User};
+use crate::notifier::discord::{DiscordDmNotifier, DiscordWebhookNotifier};
+use crate::notifier::Notifier;
+use crate::provider::review::ReviewPreview;
+use crate::provider::{following, sink, subscription};
+use crate::{notifier, provider, utility};
+use poise::serenity_prelude::CreateEmbed;
+
+pub struct UserReport {
+    pub user: User,
+    pub outcome: Outcome,
+}
+
+pub enum Outcome {
+    NotDue,
+    CrawlFailed,
+    Unchanged,
+    New {
+        review: Box<ReviewWithUser>,
+        deliveries: Vec<PlannedDelivery>,
+    },
+}
+
+/// A notification the review would trigger, with the lines describing what would be sent.
+pub struct PlannedDelivery {
+    pub target: String,
+    pub details: Vec<String>,
+}
+
+/// Crawls the users a review check would crawl and reports who would be notified,
+/// without storing reviews or contacting any delivery target.
+pub fn check_for_new_reviews() -> String {
+    let Some(users) = worker::get_users_due_for_check() else {
+        return "Failed to load the users due for a review check".to_string();
+    };
+
+    let reports: Vec<UserReport> = users
+        .into_iter()
+        .map(|user| {
+            let outcome = match provider::review::preview_new_review(&user) {
+                ReviewPreview::NotDue => Outcome::NotDue,
+                ReviewPreview::CrawlFailed => Outcome::CrawlFailed,
+                ReviewPreview::Unchanged => Outcome::Unchanged,
+                ReviewPreview::New(review) => Outcome::New {
+                    deliveries: plan_deliveries(&review),
+                    review,
+                },
+            };
+            UserReport { user, outcome }
+        })
+        .collect();
+    format_report(&reports)
+}
+
+fn plan_deliveries(review: &ReviewWithUser) -> Vec<PlannedDelivery> {
+    let user_id = review.user.id;
+    let mut deliveries = Vec::new();
+
+    for follower in following::get_followers_of_user(user_id).unwrap_or_else(|e| {
+        tracing::error!("Failed to get followings for user id {}: {}", user_id, e);
+        vec![]
+    }) {
+        let mode = digest::get_delivery_mode(follower.channel_id.as_str());
+        let mut details = Vec::new();
+        if mode == DeliveryMode::Immediate {
+            if let Some(content) = utility::mention::Mentions::from_following(&follower).content() {
+                details.push(format!("content: {}", content));
+            }
+            if follower.forum {
+                details.push("posted as a new forum post".to_string());
+            } else if follower.thread_per_user {
+                details.push(format!("posted in the thread of {}", review.user.name));
+            }
+            let language = follower.language.as_deref();
+            let original = utility::language::shows_original(follower.original_text, language, &review.review);
+            let localized = utility::language::localized_review(review, language);
+            details.push(embeds_detail(&utility::embed::get_review_embeds(&localized, original)));
+        } else {
+            details.push(format!("queued for the {} digest", mode.as_str()));
+        }
+        deliveries.push(PlannedDelivery {
+            target: DiscordWebhookNotifier::new(follower).target(),
+            details,
+        });
+    }
+
+    for subscriber in subscription::get_subscribers_of_user(user_id).unwrap_or_else(|e| {
+        tracing::error!("Failed to get subscriptions for user id {}: {}", user_id, e);
+        vec![]
+    }) {
+        let language = subscriber.language.as_deref();
+        let original = utility::language::shows_original(subscriber.original_text, language, &review.review);
+        let localized = utility::language::localized_review(review, language);
+        let details = vec![embeds_detail(&utility::embed::get_review_embeds(&localized, original))];
+        deliveries.push(PlannedDelivery {
+            target: DiscordDmNotifier::new(subscriber).target(),
+            details,
+        });
+    }
+
+    for sink in sink::get_sinks_of_user(user_id).unwrap_or_else(|e| {
+        tracing::error!("Failed to get sinks for user id {}: {}", user_id, e);
+        vec![]
+    }) {
+        let mode = digest::get_delivery_mode(digest::sink_digest_key(sink.id).as_str());
+        let (target, details) = match notifier::from_sink(&sink) {
+            Ok(sink_notifier) if mode == DeliveryMode::Immediate => (sink_notifier.target(), vec![]),
+            Ok(sink_notifier) => (sink_notifier.target(), vec![format!("queued for the {} digest", mode.as_str())]),
+            Err(e) => (format!("{} sink {}", sink.kind, sink.id), vec![format!("not delivered: {}", e)]),
+        };
+        deliveries.push(PlannedDelivery { target, details });
+    }
+    deliveries
+}
+
+fn embeds_detail(embeds: &[CreateEmbed]) -> String {
+    match serde_json::to_string_pretty(embeds) {
+        Ok(json) => format!("embeds: {}", json),
+        Err(e) => format!("embeds: failed to render: {}", e),
+    }
+}
+
+pub fn format_report(reports: &[UserReport]) -> String {
+    let count = |matches: fn(&Outcome) -> bool| reports.iter().filter(|report| matches(&report.outcome)).count();
+    let mut lines = vec![format!(
+        "Checked {} users: {} with a new review, {} unchanged, {} not due, {} failed to crawl",
+        reports.len(),
+        count(|outcome| matches!(outcome, Outcome::New { .. })),
+        count(|outcome| matches!(outcome, Outcome::Unchanged)),
+        count(|outcome| matches!(outcome, Outcome::NotDue)),
+        count(|outcome| matches!(outcome, Outcome::CrawlFailed)),
+    )];
+
+    for report in reports {
+        let user = format!("{} ({})", report.user.name, report.user.gmaps_id);
+        match &report.outcome {
+            Outcome::NotDue => lines.push(format!("{}: not due", user)),
+            Outcome::CrawlFailed => lines.push(format!("{}: crawl failed", user)),
+            Outcome::Unchanged => lines.push(format!("{}: unchanged", user)),
+            Outcome::New { review, deliveries } => {
+                lines.push(format!(
+                    "{}: new review of {} ({} stars)",
+                    user, review.review.place_name, review.review.stars
+                ));
+                if deliveries.is_empty() {
+                    lines.push("  nobody would be notified".to_string());
+                }
+                for delivery in deliveries {
+                    lines.push(format!("  -> {}", delivery.target));
+                    for detail in &delivery.details {
+                        lines.extend(detail.lines().map(|line| format!("     {}", line)));
+                    }
+                }
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_report, Outcome, PlannedDelivery, UserReport};
+    use crate::notifier::tests::sample_event;
+
+    #[test]
+    fn format_report_lists_outcomes_and_deliveries() {
+        let review = sample_event().review;
+        let reports = vec![
+            UserReport {
+                user: review.user.clone(),
+                outcome: Outcome::New {
+                    review: Box::new(review.clone()),
+                    deliveries: vec![
+                        PlannedDelivery {
+                            target: "discord channel 1".to_string(),
+                            details: vec!["content: @here".to_string(), "embeds: [\n  {}\n]".to_string()],
+                        },
+                        PlannedDelivery {
+                            target: "email reader@example.com".to_string(),
+                            details: vec!["queued for the daily digest".to_string()],
+                        },
+                    ],
+                },
+            },
+            UserReport {
+                user: review.user.clone(),
+                outcome: Outcome::Unchanged,
+            },
+        ];
+
+        assert_eq!(
+            format_report(&reports),
+            "Checked 2 users: 1 with a new review, 1 unchanged, 0 not due, 0 failed to crawl\n\
+             Alice (gmaps-42): new review of Cafe (4 stars)\n\
+             \x20 -> discord channel 1\n\
+             \x20    content: @here\n\
+             \x20    embeds: [\n\
+             \x20      {}\n\
+             \x20    ]\n\
+             \x20 -> email reader@example.com\n\
+             \x20    queued for the daily digest\n\
+             Alice (gmaps-42): unchanged"
+        );
+    }
+
+    #[test]
+    fn format_report_mentions_reviews_without_recipients() {
+        let review = sample_event().review;
+        let reports = vec![UserReport {
+            user: review.user.clone(),
+            outcome: Outcome::New {
+                review: Box::new(review),
+                deliveries: vec![],
+            },
+        }];
+
+        assert!(format_report(&reports).ends_with("new review of Cafe (4 stars)\n  nobody would be notified"));
+    }
+}
@@ -1 +1,4 @@
+pub mod digest;
+pub mod dry_run;
+pub mod reload;
 pub mod worker;
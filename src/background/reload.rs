@@ -0,0 +1,155 @@
+use crate::background::worker;
+use crate::config;
+use crate::crawler;
+use crate::notifier;
+use crate::utility;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::sync::oneshot;
+use tokio_cron_scheduler::JobScheduler;
+use uuid::Uuid;
+
+/// How often the config file is checked for changes.
+const FILE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Reloads the configuration on SIGHUP or when the config file or the selector file changes,
+/// until the process exits.
+///
+/// Reloads work before the scheduler is up; review checks are only rescheduled once it has sent its review job.
+pub async fn watch(
+    config_path: Option<PathBuf>,
+    mut hangup: Hangup,
+    mut scheduled: oneshot::Receiver<(JobScheduler, Uuid)>,
+) {
+    let mut review_check = None;
+    let mut scheduler_pending = true;
+    let mut poll = tokio::time::interval(FILE_POLL_INTERVAL);
+    let mut last_modified = watched_files_modified_at(config_path.as_deref());
+
+    loop {
+        tokio::select! {
+            started = &mut scheduled, if scheduler_pending => {
+                scheduler_pending = false;
+                review_check = started.ok();
+                continue;
+            }
+            _ = hangup.recv() => tracing::info!("Received SIGHUP, reloading configuration"),
+            _ = poll.tick() => {
+                let modified = watched_files_modified_at(config_path.as_deref());
+                if modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+                tracing::info!("Config or selector file changed, reloading configuration");
+            }
+        }
+
+        reload(config_path.as_deref(), review_check.as_mut()).await;
+    }
+}
+
+/// Swaps in the new configuration and reschedules the review job when its schedule changed.
+async fn reload(config_path: Option<&Path>, review_check: Option<&mut (JobScheduler, Uuid)>) {
+    let mut reloaded = match config::load_with_dotenv(config_path) {
+        Ok(c) => c,
+        Err(errors) => {
+            for error in errors {
+                tracing::error!("Rejected configuration reload: {}", error);
+            }
+            return;
+        }
+    };
+
+    let running = config::get_config();
+    for setting in config::retain_startup_settings(&mut reloaded, &running) {
+        tracing::warn!("Ignoring reloaded {}, changing it requires a restart", setting);
+    }
+    let schedule = reloaded.scheduler.new_review_fetch_interval.clone();
+    let previous = config::replace(reloaded);
+    utility::shorten::reset_shortener();
+    crawler::selectors::reset();
+    notifier::email::reset_transport();
+    tracing::info!("Configuration reloaded");
+
+    if previous.scheduler.new_review_fetch_interval == schedule {
+        return;
+    }
+    if let Some((scheduler, review_job_id)) = review_check
+        && let Some(job_id) = reschedule_review_check(scheduler, *review_job_id, schedule.as_str()).await
+    {
+        *review_job_id = job_id;
+    }
+}
+
+async fn reschedule_review_check(scheduler: &JobScheduler, review_job_id: Uuid, schedule: &str) -> Option<Uuid> {
+    let job = match worker::review_check_job(schedule) {
+        Ok(j) => j,
+        Err(e) => {
+            tracing::error!("Failed to create scheduled job with schedule '{}': '{}'", schedule, e);
+            return None;
+        }
+    };
+
+    let job_id = match scheduler.add(job).await {
+        Ok(id) => id,
+        Err(e) => {
+            tracing::error!("Failed to schedule review check with schedule '{}': '{}'", schedule, e);
+            return None;
+        }
+    };
+    if let Err(e) = scheduler.remove(&review_job_id).await {
+        tracing::error!("Failed to remove previous review check job: '{}'", e);
+    }
+
+    tracing::info!("Rescheduled review checks to '{}'", schedule);
+    Some(job_id)
+}
+
+fn watched_files_modified_at(config_path: Option<&Path>) -> (Option<SystemTime>, Option<SystemTime>) {
+    let selectors_file = config::get_config().crawler.selectors_file.clone();
+    (
+        modified_at(config_path),
+        modified_at(selectors_file.as_deref().map(Path::new)),
+    )
+}
+
+fn modified_at(path: Option<&Path>) -> Option<SystemTime> {
+    std::fs::metadata(path?).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Resolves on every SIGHUP; never resolves on platforms without the signal.
+/// Once created, SIGHUP no longer terminates the process.
+pub struct Hangup {
+    #[cfg(unix)]
+    signal: Option<tokio::signal::unix::Signal>,
+}
+
+impl Hangup {
+    pub fn new() -> Self {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            match signal(SignalKind::hangup()) {
+                Ok(signal) => Hangup { signal: Some(signal) },
+                Err(e) => {
+                    tracing::error!("Failed to listen for SIGHUP: {}", e);
+                    Hangup { signal: None }
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        Hangup {}
+    }
+
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if let Some(signal) = &mut self.signal {
+            if signal.recv().await.is_some() {
+                return;
+            }
+            self.signal = None;
+        }
+        std::future::pending::<()>().await
+    }
+}
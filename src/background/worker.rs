@@ -1,9 +1,14 @@
-use crate::config::get_config;
-use crate::models::{Following, ReviewWithUser, User};
-use crate::provider::following;
+use crate::background::digest;
+use crate::background::digest::DeliveryMode;
+use crate::models::{Following, Subscription, User};
+use crate::notifier::discord::{DiscordDmNotifier, DiscordWebhookNotifier};
+use crate::notifier::{EventType, ReviewEvent};
 use crate::provider::following::get_followed_users_with_old_reviews;
-use crate::{provider, utility};
-use poise::serenity_prelude as serenity;
+use crate::provider::{following, sink, subscription};
+use crate::{notifier, provider, utility};
+use tokio::task::JoinHandle;
+use tokio_cron_scheduler::{Job, JobSchedulerError};
+use tracing::Instrument;
 
 pub async fn channel_started_following_user(following: Following) {
     let Some(review) = provider::review::get_latest_review_for_user(following.followed_user_id) else {
@@ -14,27 +19,94 @@ pub async fn channel_started_following_user(following: Following) {
         return;
     };
 
-    notify_new_review(following, review).await;
+    deliver_review(following, ReviewEvent { event_type: EventType::LatestReview, review }).await;
 }
 
-pub fn check_for_new_reviews() {
-    match get_followed_users_with_old_reviews() {
-        Ok(users) => {
-            tracing::info!(
-                "Found '{}'/'{}' followed users with reviews past age limit",
-                users.len(),
-                following::get_amount_of_users_followed().unwrap()
+pub async fn user_subscribed(subscription: Subscription) {
+    let Some(review) = provider::review::get_latest_review_for_user(subscription.followed_user_id) else {
+        tracing::info!(
+                "No reviews found for newly subscribed user with id: {}",
+                subscription.followed_user_id
             );
-            process_outdated_user_reviews(users);
-        }
+        return;
+    };
+
+    let event = ReviewEvent { event_type: EventType::LatestReview, review };
+    notifier::dispatch(Box::new(DiscordDmNotifier::new(subscription)), event).await;
+}
+
+/// Job that runs `check_for_new_reviews` on the given cron schedule.
+pub fn review_check_job(schedule: &str) -> Result<Job, JobSchedulerError> {
+    Job::new(schedule, |_uuid, _l| {
+        tracing::info!("Starting scheduled review fetch...");
+        check_for_new_reviews();
+        tracing::info!("Finished scheduled review fetch.");
+    })
+}
+
+/// Returns the spawned deliveries, so one-off runs can wait for them before exiting.
+pub fn check_for_new_reviews() -> Vec<JoinHandle<()>> {
+    utility::shorten::reset_circuit_breaker();
+    let _running = utility::health::record_review_check_started();
+
+    let Some(users) = get_users_due_for_check() else { return vec![] };
+    let deliveries = process_outdated_user_reviews(users);
+    utility::health::record_review_check_completed();
+    deliveries
+}
+
+/// Followed, subscribed and sinked users whose latest stored review is past the age limit.
+pub fn get_users_due_for_check() -> Option<Vec<User>> {
+    let mut users = match get_followed_users_with_old_reviews() {
+        Ok(users) => users,
         Err(e) => {
             tracing::error!("Failed to fetch followed users with old reviews: {}", e);
+            return None;
         }
+    };
+
+    match subscription::get_subscribed_users_with_old_reviews() {
+        Ok(subscribed_users) => {
+            for user in subscribed_users {
+                if !users.iter().any(|u| u.id == user.id) {
+                    users.push(user);
+                }
+            }
+        }
+        Err(e) => tracing::error!("Failed to fetch subscribed users with old reviews: {}", e),
     }
+
+    match sink::get_sinked_users_with_old_reviews() {
+        Ok(sinked_users) => {
+            for user in sinked_users {
+                if !users.iter().any(|u| u.id == user.id) {
+                    users.push(user);
+                }
+            }
+        }
+        Err(e) => tracing::error!("Failed to fetch users with sinks and old reviews: {}", e),
+    }
+
+    tracing::info!(
+        "Found '{}' followed, subscribed or sinked users with reviews past age limit ('{}' followed in channels)",
+        users.len(),
+        following::get_amount_of_users_followed().unwrap_or_default()
+    );
+    Some(users)
 }
 
-fn process_outdated_user_reviews(users: Vec<User>) {
+fn process_outdated_user_reviews(users: Vec<User>) -> Vec<JoinHandle<()>> {
+    let mut deliveries = Vec::new();
     for user in users {
+        // Deliveries are spawned inside this span, so a review can be followed from crawl to delivery.
+        let span = tracing::info_span!(
+            "review",
+            user_id = user.id,
+            gmaps_id = %user.gmaps_id,
+            review_id = tracing::field::Empty
+        );
+        let _entered = span.enter();
+
         let Some(review) = provider::review::check_for_new_review(&user) else {
             tracing::info!(
                     "No new reviews found for followed user with id: {}",
@@ -43,126 +115,54 @@ fn process_outdated_user_reviews(users: Vec<User>) {
             continue;
         };
 
-        let followers = match following::get_followers_of_user(user.id) {
-            Ok(follows) => follows,
-            Err(e) => {
-                tracing::error!("Failed to get followings for user id {}: {}", user.id, e);
-                continue;
-            }
-        };
-
+        let followers = following::get_followers_of_user(user.id).unwrap_or_else(|e| {
+            tracing::error!("Failed to get followings for user id {}: {}", user.id, e);
+            vec![]
+        });
+        span.record("review_id", review.review.id);
+        let event = ReviewEvent { event_type: EventType::NewReview, review };
         for follower in followers {
-            let review = review.clone();
-            tokio::task::spawn(async move { notify_new_review(follower, review).await });
-        }
-    }
-}
-
-async fn notify_new_review(following: Following, review: ReviewWithUser) {
-    tracing::info!("Sending new review notification for user '{}' to channel '{}'", review.user.gmaps_id, following.channel_id);
-
-    let http = serenity::Http::new(get_config().discord_token.as_str());
-    let Some(webhook_id) = ensure_webhook_exists(
-        following.webhook_id.as_str(),
-        following.channel_id.as_str(),
-        &http,
-    )
-        .await else {
-        tracing::error!(
-                "Failed to ensure webhook exists for channel_id: {}",
-                following.channel_id
-            );
-        return;
-    };
-
-    if webhook_id != following.webhook_id {
-        match following::update_webhook(webhook_id.as_str(), following.channel_id.as_str()) {
-            Ok(()) => tracing::info!(
-                "Updated webhook ID for channel_id: {}",
-                following.channel_id
-            ),
-            Err(e) => tracing::error!(
-                "Failed to update webhook ID for channel_id: {}: {}",
-                following.channel_id,
-                e
-            ),
+            let event = event.clone();
+            deliveries.push(tokio::task::spawn(deliver_review(follower, event).in_current_span()));
         }
-    }
 
-    let webhook = match serenity::Webhook::from_id(
-        &http,
-        serenity::WebhookId::new(webhook_id.parse().unwrap()),
-    )
-        .await
-    {
-        Ok(wh) => wh,
-        Err(e) => {
-            tracing::error!("Failed to fetch webhook by ID {}: {}", webhook_id, e);
-            return;
+        let subscribers = subscription::get_subscribers_of_user(user.id).unwrap_or_else(|e| {
+            tracing::error!("Failed to get subscriptions for user id {}: {}", user.id, e);
+            vec![]
+        });
+        for subscriber in subscribers {
+            let event = event.clone();
+            deliveries.push(tokio::task::spawn(
+                notifier::dispatch(Box::new(DiscordDmNotifier::new(subscriber)), event).in_current_span(),
+            ));
         }
-    };
 
-    let current_user = match http.get_current_user().await {
-        Ok(user) => user,
-        Err(e) => {
-            tracing::error!("Failed to get current bot user: {}", e);
-            return;
-        }
-    };
-
-    let webhook_message = serenity::ExecuteWebhook::new()
-        .username(current_user.name.clone())
-        .avatar_url(current_user.avatar_url().unwrap_or_default())
-        .embed(utility::embed::get_review_embed(
-            &review,
-            following.original_text,
-        ));
-    match webhook.execute(&http, false, webhook_message).await {
-        Ok(_) => tracing::debug!(
-            "Successfully sent new review notification: gmaps='{}', channel='{}'",
-            review.user.gmaps_id,
-            following.channel_id
-        ),
-        Err(e) => tracing::error!("Failed to send webhook message: {}", e),
-    }
-}
-
-async fn ensure_webhook_exists(
-    webhook: &str,
-    channel_id: &str,
-    http: &serenity::Http,
-) -> Option<String> {
-    let webhook_id = serenity::WebhookId::new(webhook.parse().unwrap());
-
-    match http.get_webhook(webhook_id).await {
-        Ok(_) => Some(webhook.to_string()),
-        Err(e) => {
-            if let serenity::Error::Http(http_err) = &e {
-                if let serenity::HttpError::UnsuccessfulRequest(resp) = http_err {
-                    if resp.status_code == 403 {
-                        tracing::error!(
-                            "Missing permissions to access or create webhook in channel {}",
-                            channel_id
-                        );
-                        return None;
-                    }
-                }
+        let sinks = sink::get_sinks_of_user(user.id).unwrap_or_else(|e| {
+            tracing::error!("Failed to get sinks for user id {}: {}", user.id, e);
+            vec![]
+        });
+        for sink in sinks {
+            let digest_key = digest::sink_digest_key(sink.id);
+            if digest::get_delivery_mode(digest_key.as_str()) != DeliveryMode::Immediate {
+                digest::queue_review(digest_key.as_str(), &event.review);
+                continue;
             }
 
-            match http
-                .create_webhook(
-                    serenity::ChannelId::new(channel_id.parse().unwrap()),
-                    &(),
-                    None,
-                )
-                .await
-            {
-                Ok(webhook) => Some(webhook.id.to_string()),
-                Err(e) => {
-                    tracing::error!("Failed to create webhook: {}", e);
-                    None
+            match notifier::from_sink(&sink) {
+                Ok(sink_notifier) => {
+                    deliveries.push(tokio::task::spawn(notifier::dispatch(sink_notifier, event.clone()).in_current_span()));
                 }
+                Err(e) => tracing::error!("Failed to build notifier for sink {}: {}", sink.id, e),
             }
         }
     }
+    deliveries
+}
+
+async fn deliver_review(following: Following, event: ReviewEvent) {
+    if digest::get_delivery_mode(following.channel_id.as_str()) == DeliveryMode::Immediate {
+        notifier::dispatch(Box::new(DiscordWebhookNotifier::new(following)), event).await;
+    } else {
+        digest::queue_review(following.channel_id.as_str(), &event.review);
+    }
 }
@@ -0,0 +1,383 @@
+use crate::background::digest::{self, DeliveryMode};
+use crate::models::{ChannelSettings, NewReview, NewSink, User};
+use crate::provider::{following, sink, user};
+use crate::{config, crawler, notifier};
+use anyhow::Result;
+use chrono::{NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long the healthcheck waits for the HTTP server to answer.
+const HEALTHCHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Parser)]
+#[command(version, about = "Google Maps review notifications")]
+pub struct Cli {
+    /// TOML config file; `config.toml` is used when it exists
+    #[arg(long, global = true, env = "CONFIG_FILE")]
+    pub config: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the bot and the background review checks (default)
+    Run,
+    /// Apply pending database migrations and exit
+    Migrate,
+    /// Crawl a user's latest review and print it without storing anything
+    CrawlUser {
+        /// The Google Maps ID of the user
+        gmaps_id: String,
+    },
+    /// Run a single review check, deliver new reviews and exit
+    CheckNow {
+        /// Only report which reviews would be delivered, without storing or sending anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List the users followed in Discord channels
+    ListFollows,
+    /// Ask the running bot's HTTP server whether it is ready and exit non-zero when it isn't
+    Healthcheck {
+        /// Only check that the process is alive
+        #[arg(long)]
+        live: bool,
+    },
+    /// Inspect the configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Manage non-Discord delivery targets
+    #[command(subcommand)]
+    Sink(SinkCommand),
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Validate the configuration and list every problem
+    Check,
+}
+
+#[derive(Subcommand)]
+pub enum SinkCommand {
+    /// Deliver a user's new reviews to a target
+    Add {
+        kind: SinkKind,
+        /// The Google Maps ID of the user to follow
+        gmaps_id: String,
+        /// Where to deliver, e.g. the webhook URL
+        target: String,
+        /// Secret used to sign or authenticate deliveries
+        #[arg(long)]
+        secret: Option<String>,
+        /// Render the translated review text instead of the original; not available for webhook targets, which carry both
+        #[arg(long)]
+        translated: bool,
+        /// Homeserver base URL for Matrix targets, e.g. https://matrix.org
+        #[arg(long)]
+        homeserver: Option<String>,
+        #[command(flatten)]
+        schedule: DigestArgs,
+    },
+    /// Remove a target by its ID
+    Remove { id: i32 },
+    /// List all registered targets
+    List,
+}
+
+#[derive(Args)]
+pub struct DigestArgs {
+    /// Batch reviews into hourly, daily or weekly digests (email only)
+    #[arg(long, value_parser = ["immediate", "hourly", "daily", "weekly"], default_value = "immediate")]
+    digest: String,
+    /// Digest time as HH:MM (hourly digests only use the minutes)
+    #[arg(long, default_value = "09:00")]
+    digest_time: String,
+    /// IANA timezone of the digest time, e.g. Europe/Amsterdam
+    #[arg(long, default_value = "UTC")]
+    timezone: String,
+    /// Day of the week for weekly digests
+    #[arg(long, default_value = "monday")]
+    day: String,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SinkKind {
+    /// Generic JSON POST, signed with HMAC-SHA256 when a secret is set
+    Webhook,
+    /// Slack incoming webhook URL, rendered as Block Kit
+    Slack,
+    /// Matrix room ID; the access token is passed as the secret
+    Matrix,
+    /// Telegram chat ID; the bot token is passed as the secret
+    Telegram,
+    /// Email address, sent through the configured SMTP server
+    Email,
+}
+
+impl SinkKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SinkKind::Webhook => notifier::http::SINK_KIND,
+            SinkKind::Slack => notifier::slack::SINK_KIND,
+            SinkKind::Matrix => notifier::matrix::SINK_KIND,
+            SinkKind::Telegram => notifier::telegram::SINK_KIND,
+            SinkKind::Email => notifier::email::SINK_KIND,
+        }
+    }
+
+    /// Whether the target renders the review text, rather than passing on the original and translated text.
+    fn renders_text(&self) -> bool {
+        !matches!(self, SinkKind::Webhook)
+    }
+}
+
+pub fn run_sink_command(command: SinkCommand) -> Result<()> {
+    match command {
+        SinkCommand::Add { kind, gmaps_id, target, secret, translated, homeserver, schedule } => {
+            if translated && !kind.renders_text() {
+                return Err(anyhow::anyhow!("{} targets receive both texts, --translated does not apply", kind.as_str()));
+            }
+            let mut config = serde_json::json!({});
+            if kind.renders_text() {
+                config["original"] = serde_json::Value::Bool(!translated);
+            }
+            if let Some(homeserver) = homeserver {
+                config["homeserver"] = serde_json::Value::String(homeserver);
+            }
+            validate_sink(kind, secret.as_deref(), &config)?;
+            let digest_settings = build_digest_settings(kind, &schedule)?;
+
+            let user = user::get_user_from_gmaps_id(gmaps_id.as_str())?;
+            let sink = sink::add_sink(&NewSink {
+                followed_user_id: user.id,
+                kind: kind.as_str().to_string(),
+                target,
+                secret,
+                config,
+            })?;
+            println!("Added {} sink {} for '{}'", sink.kind, sink.id, user.name);
+
+            if let Some(settings) = digest_settings {
+                crate::provider::digest::save_channel_settings(&ChannelSettings {
+                    channel_id: digest::sink_digest_key(sink.id),
+                    ..settings
+                })?;
+                println!("Reviews are batched into {} digests", schedule.digest);
+            }
+        }
+        SinkCommand::Remove { id } => {
+            let sink = sink::remove_sink(id)?;
+            crate::provider::digest::delete_channel_digest(digest::sink_digest_key(id).as_str())?;
+            println!("Removed {} sink {} ({})", sink.kind, sink.id, sink.target);
+        }
+        SinkCommand::List => {
+            for (sink, user) in sink::get_all_sinks()? {
+                println!("{}\t{}\t{} ({})\t{}", sink.id, sink.kind, user.name, user.gmaps_id, sink.target);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints whether the configuration is valid; returns false when it isn't.
+pub fn check_config(config_path: Option<&Path>) -> bool {
+    let source = match config_path {
+        Some(path) => format!("{} and environment", path.display()),
+        None => "environment".to_string(),
+    };
+
+    match config::load(config_path) {
+        Ok(_) => {
+            println!("Configuration from {} is valid", source);
+            true
+        }
+        Err(errors) => {
+            println!("Configuration from {} is invalid:", source);
+            for error in errors {
+                println!("  - {}", error);
+            }
+            false
+        }
+    }
+}
+
+/// Requests `/readyz`, or `/healthz` when only liveness matters, from the HTTP server of a running bot.
+pub async fn healthcheck(live: bool) -> Result<()> {
+    let Some(bind_address) = config::get_config().http.bind_address.clone() else {
+        return Err(anyhow::anyhow!("The HTTP server is disabled, set HTTP_BIND_ADDRESS"));
+    };
+    let url = healthcheck_url(bind_address.as_str(), if live { "/healthz" } else { "/readyz" });
+
+    let response = reqwest::Client::new()
+        .get(url.as_str())
+        .timeout(HEALTHCHECK_TIMEOUT)
+        .send()
+        .await?;
+    let status = response.status();
+    print!("{}", response.text().await.unwrap_or_default());
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("{} answered {}", url, status))
+    }
+}
+
+/// Connects to loopback when the server listens on all interfaces.
+fn healthcheck_url(bind_address: &str, path: &str) -> String {
+    match bind_address.parse::<SocketAddr>() {
+        Ok(address) if address.ip().is_unspecified() => {
+            let loopback = if address.is_ipv4() { Ipv4Addr::LOCALHOST.into() } else { Ipv6Addr::LOCALHOST.into() };
+            format!("http://{}{}", SocketAddr::new(loopback, address.port()), path)
+        }
+        _ => format!("http://{}{}", bind_address, path),
+    }
+}
+
+pub fn crawl_user(gmaps_id: &str) -> Result<()> {
+    // The crawler only needs the Google Maps ID; nothing is looked up or stored.
+    let user = User {
+        id: 0,
+        gmaps_id: gmaps_id.to_string(),
+        name: String::new(),
+    };
+    let review = crawler::pages::review::get_latest_review_for_user(&user)?;
+    println!("{}", format_new_review(&review));
+    Ok(())
+}
+
+pub fn list_follows() -> Result<()> {
+    for (following, user) in following::get_all_followings()? {
+        let text = if following.original_text { "original" } else { "translated" };
+        let language = following.language.as_deref().unwrap_or("-");
+        println!(
+            "{}\t{}\t{} ({})\t{}\t{}",
+            following.id, following.channel_id, user.name, user.gmaps_id, text, language
+        );
+    }
+    Ok(())
+}
+
+fn format_new_review(review: &NewReview) -> String {
+    let pictures: Vec<&str> = review
+        .pictures
+        .as_array()
+        .map(|pictures| pictures.iter().filter_map(|picture| picture.as_str()).collect())
+        .unwrap_or_default();
+
+    let mut lines = vec![
+        format!("Place:    {}", review.place_name),
+        format!("Stars:    {}", review.stars),
+        format!("Link:     {}", review.link_en),
+        format!("Text:     {}", review.text),
+    ];
+    if let Some(original_text) = &review.original_text {
+        lines.push(format!("Original: {}", original_text));
+    }
+    if let Some(language) = &review.language {
+        lines.push(format!("Language: {}", language));
+    }
+    lines.push(format!("Pictures: {}", pictures.len()));
+    lines.extend(pictures.iter().map(|picture| format!("  {}", picture)));
+    lines.join("\n")
+}
+
+/// Rejects sinks that would only fail once a review is delivered.
+fn validate_sink(kind: SinkKind, secret: Option<&str>, config: &serde_json::Value) -> Result<()> {
+    match kind {
+        SinkKind::Matrix => {
+            if config.get("homeserver").is_none() {
+                return Err(anyhow::anyhow!("Matrix targets need --homeserver"));
+            }
+            if secret.is_none() {
+                return Err(anyhow::anyhow!("Matrix targets need the access token as --secret"));
+            }
+        }
+        SinkKind::Telegram => {
+            if secret.is_none() {
+                return Err(anyhow::anyhow!("Telegram targets need the bot token as --secret"));
+            }
+        }
+        SinkKind::Email => {
+            if crate::config::get_config().sinks.smtp.host.is_none() {
+                return Err(anyhow::anyhow!("Email targets need SMTP_HOST to be configured"));
+            }
+        }
+        SinkKind::Webhook | SinkKind::Slack => {}
+    }
+    Ok(())
+}
+
+/// Builds the digest schedule for a new sink, keyed once the sink has an ID.
+fn build_digest_settings(kind: SinkKind, args: &DigestArgs) -> Result<Option<ChannelSettings>> {
+    let mode = DeliveryMode::parse(args.digest.as_str()).unwrap_or(DeliveryMode::Immediate);
+    if mode == DeliveryMode::Immediate {
+        return Ok(None);
+    }
+    if !matches!(kind, SinkKind::Email) {
+        return Err(anyhow::anyhow!("Only email targets support digests"));
+    }
+
+    let digest_time = NaiveTime::parse_from_str(args.digest_time.trim(), "%H:%M")
+        .map_err(|_| anyhow::anyhow!("Invalid digest time '{}', expected HH:MM", args.digest_time))?;
+    if args.timezone.parse::<Tz>().is_err() {
+        return Err(anyhow::anyhow!("Unknown timezone '{}'", args.timezone));
+    }
+    let Ok(weekday) = args.day.parse::<Weekday>() else {
+        return Err(anyhow::anyhow!("Unknown day '{}'", args.day));
+    };
+
+    Ok(Some(ChannelSettings {
+        channel_id: String::new(),
+        delivery_mode: mode.as_str().to_string(),
+        digest_time,
+        digest_weekday: weekday.number_from_monday() as i32,
+        timezone: args.timezone.clone(),
+        last_digest_at: Some(Utc::now().naive_utc()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_new_review, healthcheck_url, Cli};
+    use crate::models::NewReview;
+    use clap::CommandFactory;
+    use serde_json::json;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn format_new_review_lists_fields_and_pictures() {
+        let review = NewReview {
+            place_name: "Cafe".to_string(),
+            text: "Great coffee".to_string(),
+            original_text: Some("Geweldige koffie".to_string()),
+            stars: 4,
+            user_id: 0,
+            link_en: "https://example.com/review".to_string(),
+            pictures: json!(["https://img/1", null, "https://img/2"]),
+            language: Some("nl".to_string()),
+        };
+
+        assert_eq!(
+            format_new_review(&review),
+            "Place:    Cafe\nStars:    4\nLink:     https://example.com/review\nText:     Great coffee\n\
+             Original: Geweldige koffie\nLanguage: nl\nPictures: 2\n  https://img/1\n  https://img/2"
+        );
+    }
+
+    #[test]
+    fn healthcheck_url_uses_loopback_for_wildcard_addresses() {
+        assert_eq!(healthcheck_url("0.0.0.0:8080", "/readyz"), "http://127.0.0.1:8080/readyz");
+        assert_eq!(healthcheck_url("[::]:8080", "/healthz"), "http://[::1]:8080/healthz");
+        assert_eq!(healthcheck_url("10.0.0.5:9000", "/readyz"), "http://10.0.0.5:9000/readyz");
+        assert_eq!(healthcheck_url("localhost:8080", "/readyz"), "http://localhost:8080/readyz");
+    }
+}
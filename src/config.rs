@@ -1,30 +1,778 @@
-use std::sync::LazyLock;
+use crate::crawler::selectors;
+use crate::utility::language;
+use arc_swap::ArcSwap;
+use croner::parser::{CronParser, Seconds};
+use croner::Cron;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
 
+/// Read when no config file is given explicitly, if it exists.
+static DEFAULT_CONFIG_FILE: &str = "config.toml";
+static CONFIG: OnceLock<ArcSwap<Config>> = OnceLock::new();
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
     pub star_text: String,
-    pub fetch_reviews_on_startup: bool,
-    pub new_review_fetch_interval: String,
     pub discord_token: String,
     pub database_url: String,
+    pub scheduler: SchedulerConfig,
+    pub crawler: CrawlerConfig,
+    pub http: HttpConfig,
+    pub shortener: ShortenerConfig,
+    pub sinks: SinksConfig,
+    pub logging: LoggingConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchedulerConfig {
+    pub fetch_reviews_on_startup: bool,
+    /// Cron schedule with seconds, e.g. `0 0 */6 * * *`.
+    pub new_review_fetch_interval: String,
     pub review_age_limit_hours: i64,
 }
 
-static CONFIG: LazyLock<Config> = LazyLock::new(|| Config {
-    star_text: std::env::var("STAR_TEXT").unwrap_or_else(|_| "⭐".to_string()),
-    fetch_reviews_on_startup: std::env::var("FETCH_REVIEWS_ON_STARTUP")
-        .unwrap_or_else(|_| "true".to_string())
-        .to_lowercase()
-        == "true",
-    new_review_fetch_interval: std::env::var("NEW_REVIEW_FETCH_INTERVAL")
-        .unwrap_or_else(|_| "0 0 */6 * * *".to_string()),
-    discord_token: std::env::var("DISCORD_TOKEN").expect("DISCORD_TOKEN must be set"),
-    database_url: std::env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
-    review_age_limit_hours: std::env::var("REVIEW_AGE_LIMIT_HOURS")
-        .unwrap_or_else(|_| "24".to_string())
-        .parse()
-        .unwrap_or(24),
-});
-
-pub fn get_config() -> &'static Config {
-    &CONFIG
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CrawlerConfig {
+    /// Chrome executable to launch; found automatically when unset.
+    pub chrome_path: Option<String>,
+    pub window_width: u32,
+    pub window_height: u32,
+    /// Default timeout of the browser tab when waiting for elements.
+    pub page_timeout_secs: u64,
+    /// Directory where a screenshot and the HTML of the page are saved when a crawl fails; disabled when unset.
+    pub debug_dir: Option<String>,
+    /// Only this many of the newest failure captures are kept.
+    pub debug_max_captures: usize,
+    /// Failure captures older than this are deleted.
+    pub debug_max_age_hours: i64,
+    /// Selector file whose chains replace the built-in ones of the same field.
+    pub selectors_file: Option<String>,
+    /// How review data is read from the Google Maps pages.
+    pub extraction: Extraction,
+    /// Language Google Maps is crawled in, and reviews are translated to.
+    pub language: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Extraction {
+    /// Reviews are parsed from the data embedded in the page and its network responses,
+    /// falling back to reading the rendered page. Experimental, as the data layout is undocumented.
+    Embedded,
+    /// Reviews are read from the rendered page only.
+    Dom,
+}
+
+impl FromStr for Extraction {
+    type Err = String;
+
+    fn from_str(extraction: &str) -> Result<Self, Self::Err> {
+        match extraction.to_lowercase().as_str() {
+            "embedded" => Ok(Extraction::Embedded),
+            "dom" => Ok(Extraction::Dom),
+            _ => Err("expected embedded or dom".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    /// The HTTP server is disabled when this is empty.
+    pub bind_address: Option<String>,
+    /// Externally reachable base URL of the HTTP server, used for feed self links and short links.
+    pub public_url: Option<String>,
+    /// Secret that channel feeds must be requested with; they are not served when this is empty.
+    pub feed_token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    pub format: LogFormat,
+    /// Base URL of an OTLP/HTTP collector, e.g. `http://otel-collector:4318`; spans are only exported when set.
+    pub otlp_endpoint: Option<String>,
+    /// Service name attached to exported spans.
+    pub service_name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// One line per event, readable in a terminal.
+    Text,
+    /// Multi-line events with their fields and spans laid out, for local debugging.
+    Pretty,
+    /// One JSON object per event with its fields and spans, for log aggregation.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err("expected text, pretty or json".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SinksConfig {
+    pub smtp: SmtpConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SmtpConfig {
+    /// Email delivery is disabled when no host is configured.
+    pub host: Option<String>,
+    pub port: u16,
+    pub tls: SmtpTls,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    /// Directory with `review.{html,txt}` and `digest.{html,txt}` overriding the built-in templates.
+    pub template_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    /// Plain connection upgraded with STARTTLS, usually on port 587.
+    StartTls,
+    /// Implicit TLS, usually on port 465.
+    Tls,
+    /// Unencrypted, only meant for local relays and testing.
+    None,
+}
+
+impl FromStr for SmtpTls {
+    type Err = String;
+
+    fn from_str(tls: &str) -> Result<Self, Self::Err> {
+        match tls.to_lowercase().as_str() {
+            "starttls" => Ok(SmtpTls::StartTls),
+            "tls" => Ok(SmtpTls::Tls),
+            "none" => Ok(SmtpTls::None),
+            _ => Err("expected starttls, tls or none".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShortenerConfig {
+    pub kind: ShortenerKind,
+    /// Endpoint of the external shortener.
+    pub url: Option<String>,
+    /// JSON body sent to the external shortener, with `{url}` replaced by the long URL.
+    /// The long URL is posted as the plain body when unset.
+    pub request_template: Option<String>,
+    /// JSON pointer to the short URL in the external shortener's response.
+    pub response_pointer: String,
+    /// Format of the returned short URL, with `{}` replaced by the value found at the pointer.
+    pub result_format: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShortenerKind {
+    /// Links are stored with their original URL.
+    Disabled,
+    /// Links are shortened by a configurable HTTP service.
+    External,
+    /// Links are stored in the database and redirected by the built-in HTTP server.
+    Builtin,
+}
+
+impl FromStr for ShortenerKind {
+    type Err = String;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind.to_lowercase().as_str() {
+            "disabled" => Ok(ShortenerKind::Disabled),
+            "external" => Ok(ShortenerKind::External),
+            "builtin" => Ok(ShortenerKind::Builtin),
+            _ => Err("expected disabled, external or builtin".to_string()),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            star_text: "⭐".to_string(),
+            discord_token: String::new(),
+            database_url: String::new(),
+            scheduler: SchedulerConfig::default(),
+            crawler: CrawlerConfig::default(),
+            http: HttpConfig::default(),
+            shortener: ShortenerConfig::default(),
+            sinks: SinksConfig::default(),
+            logging: LoggingConfig::default(),
+        }
+    }
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig {
+            fetch_reviews_on_startup: true,
+            new_review_fetch_interval: "0 0 */6 * * *".to_string(),
+            review_age_limit_hours: 24,
+        }
+    }
+}
+
+impl Default for CrawlerConfig {
+    fn default() -> Self {
+        CrawlerConfig {
+            chrome_path: None,
+            window_width: 1920,
+            window_height: 1080,
+            page_timeout_secs: 10,
+            debug_dir: None,
+            debug_max_captures: 20,
+            debug_max_age_hours: 168,
+            selectors_file: None,
+            extraction: Extraction::Dom,
+            language: "en".to_string(),
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            bind_address: Some("127.0.0.1:8080".to_string()),
+            public_url: None,
+            feed_token: None,
+        }
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
+            format: LogFormat::Text,
+            otlp_endpoint: None,
+            service_name: "gmaps-review-notif".to_string(),
+        }
+    }
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        SmtpConfig {
+            host: None,
+            port: 587,
+            tls: SmtpTls::StartTls,
+            username: None,
+            password: None,
+            from: "Google Maps Reviews <reviews@localhost>".to_string(),
+            template_dir: None,
+        }
+    }
+}
+
+impl Default for ShortenerConfig {
+    fn default() -> Self {
+        ShortenerConfig {
+            kind: ShortenerKind::Disabled,
+            url: None,
+            request_template: None,
+            response_pointer: "/url".to_string(),
+            result_format: "{}".to_string(),
+        }
+    }
+}
+
+/// Environment variables override values from the config file.
+/// Each one can also be read from the file named by `<NAME>_FILE`, e.g. a Docker secret.
+struct EnvOverrides<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+    errors: Vec<String>,
+}
+
+impl EnvOverrides<'_> {
+    fn apply(&mut self, config: &mut Config) {
+        self.string("STAR_TEXT", &mut config.star_text);
+        self.string("DISCORD_TOKEN", &mut config.discord_token);
+        self.string("DATABASE_URL", &mut config.database_url);
+
+        let scheduler = &mut config.scheduler;
+        self.flag("FETCH_REVIEWS_ON_STARTUP", &mut scheduler.fetch_reviews_on_startup);
+        self.string("NEW_REVIEW_FETCH_INTERVAL", &mut scheduler.new_review_fetch_interval);
+        self.parsed("REVIEW_AGE_LIMIT_HOURS", &mut scheduler.review_age_limit_hours);
+
+        let crawler = &mut config.crawler;
+        self.optional("CHROME_PATH", &mut crawler.chrome_path);
+        self.parsed("CRAWLER_WINDOW_WIDTH", &mut crawler.window_width);
+        self.parsed("CRAWLER_WINDOW_HEIGHT", &mut crawler.window_height);
+        self.parsed("CRAWLER_PAGE_TIMEOUT_SECS", &mut crawler.page_timeout_secs);
+        self.optional("CRAWLER_DEBUG_DIR", &mut crawler.debug_dir);
+        self.parsed("CRAWLER_DEBUG_MAX_CAPTURES", &mut crawler.debug_max_captures);
+        self.parsed("CRAWLER_DEBUG_MAX_AGE_HOURS", &mut crawler.debug_max_age_hours);
+        self.optional("CRAWLER_SELECTORS_FILE", &mut crawler.selectors_file);
+        self.parsed("CRAWLER_EXTRACTION", &mut crawler.extraction);
+        self.string("CRAWLER_LANGUAGE", &mut crawler.language);
+
+        self.optional("HTTP_BIND_ADDRESS", &mut config.http.bind_address);
+        self.optional("PUBLIC_URL", &mut config.http.public_url);
+        self.optional("FEED_TOKEN", &mut config.http.feed_token);
+
+        let shortener = &mut config.shortener;
+        self.parsed("SHORTENER", &mut shortener.kind);
+        self.optional("SHORTENER_URL", &mut shortener.url);
+        self.optional("SHORTENER_REQUEST_TEMPLATE", &mut shortener.request_template);
+        self.string("SHORTENER_RESPONSE_POINTER", &mut shortener.response_pointer);
+        self.string("SHORTENER_RESULT_FORMAT", &mut shortener.result_format);
+
+        let smtp = &mut config.sinks.smtp;
+        self.optional("SMTP_HOST", &mut smtp.host);
+        self.parsed("SMTP_PORT", &mut smtp.port);
+        self.parsed("SMTP_TLS", &mut smtp.tls);
+        self.optional("SMTP_USERNAME", &mut smtp.username);
+        self.optional("SMTP_PASSWORD", &mut smtp.password);
+        self.string("SMTP_FROM", &mut smtp.from);
+        self.optional("EMAIL_TEMPLATE_DIR", &mut smtp.template_dir);
+
+        let logging = &mut config.logging;
+        self.parsed("LOG_FORMAT", &mut logging.format);
+        self.optional("OTEL_EXPORTER_OTLP_ENDPOINT", &mut logging.otlp_endpoint);
+        self.string("OTEL_SERVICE_NAME", &mut logging.service_name);
+    }
+
+    fn value(&mut self, name: &str) -> Option<String> {
+        if let Some(value) = (self.lookup)(name) {
+            return Some(value);
+        }
+
+        let file_name = format!("{}_FILE", name);
+        let path = (self.lookup)(file_name.as_str())?;
+        match std::fs::read_to_string(path.as_str()) {
+            Ok(contents) => Some(contents.trim_end_matches(['\r', '\n']).to_string()),
+            Err(e) => {
+                self.errors.push(format!("{}: failed to read '{}': {}", file_name, path, e));
+                None
+            }
+        }
+    }
+
+    fn string(&mut self, name: &str, target: &mut String) {
+        if let Some(value) = self.value(name) {
+            *target = value;
+        }
+    }
+
+    fn optional(&mut self, name: &str, target: &mut Option<String>) {
+        if let Some(value) = self.value(name) {
+            *target = Some(value);
+        }
+    }
+
+    fn parsed<T: FromStr>(&mut self, name: &str, target: &mut T)
+    where
+        T::Err: Display,
+    {
+        let Some(value) = self.value(name) else { return };
+        match value.trim().parse() {
+            Ok(parsed) => *target = parsed,
+            Err(e) => self.errors.push(format!("{}: invalid value '{}': {}", name, value, e)),
+        }
+    }
+
+    fn flag(&mut self, name: &str, target: &mut bool) {
+        let Some(value) = self.value(name) else { return };
+        match value.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" => *target = true,
+            "false" | "0" | "no" => *target = false,
+            _ => self.errors.push(format!("{}: invalid value '{}': expected true or false", name, value)),
+        }
+    }
+}
+
+impl Config {
+    /// Treats empty optional settings as unset, so they can be cleared from the environment.
+    fn normalize(&mut self) {
+        let optionals = [
+            &mut self.crawler.chrome_path,
+            &mut self.crawler.debug_dir,
+            &mut self.crawler.selectors_file,
+            &mut self.http.bind_address,
+            &mut self.http.public_url,
+            &mut self.http.feed_token,
+            &mut self.shortener.url,
+            &mut self.shortener.request_template,
+            &mut self.sinks.smtp.host,
+            &mut self.sinks.smtp.username,
+            &mut self.sinks.smtp.password,
+            &mut self.sinks.smtp.template_dir,
+            &mut self.logging.otlp_endpoint,
+        ];
+        for value in optionals {
+            if value.as_deref().is_some_and(|v| v.trim().is_empty()) {
+                *value = None;
+            }
+        }
+    }
+
+    /// Returns every problem with the configuration rather than stopping at the first.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if self.discord_token.trim().is_empty() {
+            errors.push("discord_token must be set (DISCORD_TOKEN)".to_string());
+        }
+        if self.database_url.trim().is_empty() {
+            errors.push("database_url must be set (DATABASE_URL)".to_string());
+        } else if !self.database_url.starts_with("postgres://") && !self.database_url.starts_with("postgresql://") {
+            errors.push("database_url must be a postgres:// URL".to_string());
+        }
+
+        if let Err(e) = validate_cron(self.scheduler.new_review_fetch_interval.as_str()) {
+            errors.push(format!(
+                "scheduler.new_review_fetch_interval: invalid cron schedule '{}': {}",
+                self.scheduler.new_review_fetch_interval, e
+            ));
+        }
+        if self.scheduler.review_age_limit_hours < 1 {
+            errors.push("scheduler.review_age_limit_hours must be at least 1".to_string());
+        }
+
+        if self.crawler.window_width == 0 || self.crawler.window_height == 0 {
+            errors.push("crawler.window_width and crawler.window_height must be positive".to_string());
+        }
+        if !(1..=300).contains(&self.crawler.page_timeout_secs) {
+            errors.push("crawler.page_timeout_secs must be between 1 and 300".to_string());
+        }
+        if self.crawler.debug_dir.is_some() {
+            if self.crawler.debug_max_captures == 0 {
+                errors.push("crawler.debug_max_captures must be at least 1".to_string());
+            }
+            if self.crawler.debug_max_age_hours < 1 {
+                errors.push("crawler.debug_max_age_hours must be at least 1".to_string());
+            }
+        }
+        if let Err(e) = language::parse_language_code(self.crawler.language.as_str()) {
+            errors.push(format!("crawler.language: {}", e));
+        }
+        if let Some(Err(e)) = self.crawler.selectors_file.as_deref().map(|path| selectors::load(Some(Path::new(path)))) {
+            errors.push(format!("crawler.selectors_file: {}", e));
+        }
+
+        if let Some(Err(e)) = self.http.public_url.as_deref().map(validate_http_url) {
+            errors.push(format!("http.public_url: {}", e));
+        }
+
+        match self.shortener.kind {
+            ShortenerKind::Disabled => {}
+            ShortenerKind::External => {
+                match &self.shortener.url {
+                    Some(url) => {
+                        if let Err(e) = validate_http_url(url) {
+                            errors.push(format!("shortener.url: {}", e));
+                        }
+                    }
+                    None => errors.push("shortener.url must be set for the external shortener".to_string()),
+                }
+                if self.shortener.request_template.as_ref().is_some_and(|template| !template.contains("{url}")) {
+                    errors.push("shortener.request_template must contain {url}".to_string());
+                }
+                if !self.shortener.response_pointer.is_empty() && !self.shortener.response_pointer.starts_with('/') {
+                    errors.push("shortener.response_pointer must be a JSON pointer starting with /".to_string());
+                }
+                if !self.shortener.result_format.contains("{}") {
+                    errors.push("shortener.result_format must contain {}".to_string());
+                }
+            }
+            ShortenerKind::Builtin => {
+                if self.http.public_url.is_none() || self.http.bind_address.is_none() {
+                    errors.push("the builtin shortener needs http.bind_address and http.public_url".to_string());
+                }
+            }
+        }
+
+        let smtp = &self.sinks.smtp;
+        if smtp.host.is_some() {
+            if smtp.port == 0 {
+                errors.push("sinks.smtp.port must be positive".to_string());
+            }
+            if smtp.from.parse::<lettre::message::Mailbox>().is_err() {
+                errors.push(format!("sinks.smtp.from: invalid address '{}'", smtp.from));
+            }
+            if smtp.username.is_some() != smtp.password.is_some() {
+                errors.push("sinks.smtp.username and sinks.smtp.password must be set together".to_string());
+            }
+        }
+
+        if let Some(Err(e)) = self.logging.otlp_endpoint.as_deref().map(validate_http_url) {
+            errors.push(format!("logging.otlp_endpoint: {}", e));
+        }
+        if self.logging.service_name.trim().is_empty() {
+            errors.push("logging.service_name must not be empty".to_string());
+        }
+
+        errors
+    }
+}
+
+/// Returns the config file to read: the given path, or `config.toml` in the working directory when it exists.
+pub fn resolve_path(path: Option<&Path>) -> Option<PathBuf> {
+    match path {
+        Some(path) => Some(path.to_path_buf()),
+        None => Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|path| path.exists()),
+    }
+}
+
+/// Loads defaults, then the config file, then environment overrides, and validates the result.
+pub fn load(path: Option<&Path>) -> Result<Config, Vec<String>> {
+    load_from(path, &|name| std::env::var(name).ok())
+}
+
+/// Like `load`, with the variables of the `.env` file taking precedence over the environment as they do at startup.
+/// The process environment is left as it is, since changing it while other threads run is unsound.
+pub fn load_with_dotenv(path: Option<&Path>) -> Result<Config, Vec<String>> {
+    let dotenv: HashMap<String, String> = match dotenvy::dotenv_iter() {
+        Ok(vars) => vars
+            .collect::<Result<_, _>>()
+            .map_err(|e| vec![format!(".env: {}", e)])?,
+        Err(_) => HashMap::new(),
+    };
+    load_from(path, &|name| dotenv.get(name).cloned().or_else(|| std::env::var(name).ok()))
+}
+
+fn load_from(path: Option<&Path>, lookup: &dyn Fn(&str) -> Option<String>) -> Result<Config, Vec<String>> {
+    let mut errors = Vec::new();
+    let mut config = match path.map(read_config_file) {
+        Some(Ok(config)) => config,
+        Some(Err(e)) => {
+            errors.push(e);
+            Config::default()
+        }
+        None => Config::default(),
+    };
+
+    let mut overrides = EnvOverrides { lookup, errors: Vec::new() };
+    overrides.apply(&mut config);
+    config.normalize();
+
+    errors.extend(overrides.errors);
+    errors.extend(config.validate());
+    if errors.is_empty() {
+        Ok(config)
+    } else {
+        Err(errors)
+    }
+}
+
+fn read_config_file(path: &Path) -> Result<Config, String> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => return Err(format!("Failed to read config file '{}': {}", path.display(), e)),
+    };
+    toml::from_str(contents.as_str()).map_err(|e| format!("Invalid config file '{}': {}", path.display(), e))
+}
+
+fn validate_cron(schedule: &str) -> Result<(), String> {
+    parse_cron(schedule).map(|_| ())
+}
+
+/// Parses a cron schedule the way the job scheduler reads it, with seconds.
+pub fn parse_cron(schedule: &str) -> Result<Cron, String> {
+    CronParser::builder()
+        .seconds(Seconds::Required)
+        .dom_and_dow(true)
+        .build()
+        .parse(schedule)
+        .map_err(|e| e.to_string())
+}
+
+fn validate_http_url(url: &str) -> Result<(), String> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if parsed.scheme() == "http" || parsed.scheme() == "https" => Ok(()),
+        Ok(_) => Err(format!("'{}' must be an http(s) URL", url)),
+        Err(e) => Err(format!("invalid URL '{}': {}", url, e)),
+    }
+}
+
+/// Makes the loaded configuration available through `get_config`; must run once at startup.
+pub fn init(config: Config) {
+    if CONFIG.set(ArcSwap::from_pointee(config)).is_err() {
+        tracing::warn!("Configuration was already initialized");
+    }
+}
+
+/// Swaps in a reloaded configuration and returns the previous one.
+/// Callers that already hold the previous configuration keep using it until they drop it.
+pub fn replace(config: Config) -> Arc<Config> {
+    CONFIG.get().expect("configuration is not loaded").swap(Arc::new(config))
+}
+
+pub fn get_config() -> Arc<Config> {
+    CONFIG.get().expect("configuration is not loaded").load_full()
+}
+
+/// Copies the settings that are only read at startup from the running configuration,
+/// returning the names of those the reloaded configuration tried to change.
+pub fn retain_startup_settings(reloaded: &mut Config, running: &Config) -> Vec<&'static str> {
+    let mut changed = Vec::new();
+    if reloaded.discord_token != running.discord_token {
+        changed.push("discord_token");
+        reloaded.discord_token = running.discord_token.clone();
+    }
+    if reloaded.database_url != running.database_url {
+        changed.push("database_url");
+        reloaded.database_url = running.database_url.clone();
+    }
+    if reloaded.http.bind_address != running.http.bind_address {
+        changed.push("http.bind_address");
+        reloaded.http.bind_address = running.http.bind_address.clone();
+    }
+    if reloaded.logging != running.logging {
+        changed.push("logging");
+        reloaded.logging = running.logging.clone();
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_from, retain_startup_settings, Config, Extraction, LogFormat, ShortenerKind, SmtpTls};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("gmaps-review-notif-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn load_reads_sections_from_file() {
+        let path = write_temp(
+            "sections.toml",
+            r#"
+                discord_token = "token"
+                database_url = "postgres://localhost/reviews"
+
+                [scheduler]
+                review_age_limit_hours = 48
+
+                [shortener]
+                kind = "external"
+                url = "https://s.example.com/shorten"
+
+                [sinks.smtp]
+                host = "smtp.example.com"
+                tls = "tls"
+            "#,
+        );
+        let config = load_from(Some(path.as_path()), &env(&[])).unwrap();
+
+        assert_eq!(config.scheduler.review_age_limit_hours, 48);
+        assert_eq!(config.scheduler.new_review_fetch_interval, "0 0 */6 * * *");
+        assert_eq!(config.shortener.kind, ShortenerKind::External);
+        assert_eq!(config.sinks.smtp.tls, SmtpTls::Tls);
+        assert_eq!(config.sinks.smtp.port, 587);
+        assert_eq!(config.http.bind_address.as_deref(), Some("127.0.0.1:8080"));
+    }
+
+    #[test]
+    fn env_overrides_file_and_reads_secret_files() {
+        let path = write_temp("override.toml", "discord_token = \"from-file\"\nstar_text = \"*\"\n");
+        let secret = write_temp("token.secret", "from-secret\n");
+        let secret = secret.to_str().unwrap();
+        let config = load_from(
+            Some(path.as_path()),
+            &env(&[
+                ("DISCORD_TOKEN_FILE", secret),
+                ("DATABASE_URL", "postgres://localhost/reviews"),
+                ("STAR_TEXT", "+"),
+                ("HTTP_BIND_ADDRESS", ""),
+                ("LOG_FORMAT", "JSON"),
+                ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://collector:4318"),
+                ("CRAWLER_EXTRACTION", "embedded"),
+            ]),
+        )
+            .unwrap();
+
+        assert_eq!(config.discord_token, "from-secret");
+        assert_eq!(config.star_text, "+");
+        assert_eq!(config.http.bind_address, None);
+        assert_eq!(config.logging.format, LogFormat::Json);
+        assert_eq!(config.logging.otlp_endpoint.as_deref(), Some("http://collector:4318"));
+        assert_eq!(config.crawler.extraction, Extraction::Embedded);
+    }
+
+    #[test]
+    fn load_reports_all_errors() {
+        let errors = load_from(
+            None,
+            &env(&[
+                ("DATABASE_URL", "mysql://localhost"),
+                ("REVIEW_AGE_LIMIT_HOURS", "24h"),
+                ("NEW_REVIEW_FETCH_INTERVAL", "every hour"),
+                ("SHORTENER", "external"),
+                ("CRAWLER_LANGUAGE", "english"),
+            ]),
+        )
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 6, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.starts_with("REVIEW_AGE_LIMIT_HOURS: invalid value '24h'")));
+        assert!(errors.iter().any(|e| e.starts_with("discord_token must be set")));
+        assert!(errors.iter().any(|e| e.starts_with("database_url must be a postgres")));
+        assert!(errors.iter().any(|e| e.starts_with("scheduler.new_review_fetch_interval")));
+        assert!(errors.iter().any(|e| e.starts_with("shortener.url must be set")));
+        assert!(errors.iter().any(|e| e.starts_with("crawler.language: 'english' is not a language code")));
+    }
+
+    #[test]
+    fn load_rejects_unknown_keys() {
+        let path = write_temp("unknown.toml", "[scheduler]\nreview_age_limit = 12\n");
+        let errors = load_from(
+            Some(path.as_path()),
+            &env(&[("DISCORD_TOKEN", "token"), ("DATABASE_URL", "postgres://localhost/reviews")]),
+        )
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("unknown field `review_age_limit`"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn retain_startup_settings_keeps_running_values() {
+        let running = Config {
+            discord_token: "token".to_string(),
+            database_url: "postgres://localhost/reviews".to_string(),
+            ..Config::default()
+        };
+        let mut reloaded = Config {
+            discord_token: "other-token".to_string(),
+            star_text: "*".to_string(),
+            ..running.clone()
+        };
+
+        assert_eq!(retain_startup_settings(&mut reloaded, &running), vec!["discord_token"]);
+        assert_eq!(reloaded.discord_token, "token");
+        assert_eq!(reloaded.star_text, "*");
+    }
 }
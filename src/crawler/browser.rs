@@ -1,6 +1,9 @@
+use crate::config::get_config;
+use crate::crawler::selectors;
 use anyhow::Result;
 use headless_chrome::{Browser, LaunchOptions};
 use std::ops::Deref;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -22,13 +25,17 @@ impl Deref for AutoClosableTab {
 }
 
 pub fn get(accept_terms: bool) -> Result<Browser> {
+    let config = get_config();
+    let crawler = &config.crawler;
+    let language = format!("--lang={}", crawler.language);
     let browser = Browser::new(LaunchOptions {
         headless: false,
-        window_size: Some((1920, 1080)),
+        window_size: Some((crawler.window_width, crawler.window_height)),
+        path: crawler.chrome_path.as_ref().map(PathBuf::from),
         args: vec![
             "--headless=new".as_ref(),
             "--no-sandbox".as_ref(),
-            "--lang=en-US".as_ref(),
+            language.as_ref(),
         ],
         ..Default::default()
     });
@@ -52,7 +59,7 @@ pub fn new_tab(browser: &Browser) -> Result<AutoClosableTab> {
             return Err(anyhow::anyhow!("Failed to enable stealth mode: {e}"));
         }
     }
-    tab.set_default_timeout(Duration::from_secs(10));
+    tab.set_default_timeout(Duration::from_secs(get_config().crawler.page_timeout_secs));
     Ok(AutoClosableTab { tab })
 }
 
@@ -63,7 +70,7 @@ pub fn accept_gmaps_terms(browser: &Browser) -> Result<()> {
     tab.wait_until_navigated()?;
 
     while tab.get_url().contains("consent.google.com") {
-        let accept_button = tab.wait_for_xpath(r#"//form[contains(@action, "consent.google.com")]//button[contains(@aria-label, "Accept all")]"#);
+        let accept_button = selectors::wait_for_element(&tab, &selectors::current().consent.accept_button);
         match accept_button {
             Ok(button) => {
                 button.click()?;
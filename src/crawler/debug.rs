@@ -0,0 +1,175 @@
+use crate::config::get_config;
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use headless_chrome::protocol::cdp::Page;
+use headless_chrome::Tab;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Capture names start with this timestamp, so they sort by age.
+static TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+const TIMESTAMP_LEN: usize = "20260101T000000000Z".len();
+static CAPTURE_EXTENSIONS: [&str; 2] = ["png", "html"];
+
+/// Adds the files of a failure capture of the tab to the crawl error, when captures are enabled.
+pub fn attach_capture(error: anyhow::Error, tab: &Tab, user_id: &str) -> anyhow::Error {
+    let files = capture_failure(tab, user_id);
+    if files.is_empty() {
+        return error;
+    }
+
+    let files: Vec<String> = files.iter().map(|file| file.display().to_string()).collect();
+    anyhow::anyhow!("{} (debug capture: {})", error, files.join(", "))
+}
+
+/// Saves a screenshot and the HTML of the page to the debug directory, prunes old captures
+/// and returns the files that were written.
+fn capture_failure(tab: &Tab, user_id: &str) -> Vec<PathBuf> {
+    let config = get_config();
+    let crawler = &config.crawler;
+    let Some(dir) = crawler.debug_dir.as_ref().map(PathBuf::from) else { return vec![] };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        tracing::error!("Failed to create crawler debug directory '{}': {}", dir.display(), e);
+        return vec![];
+    }
+
+    let now = Utc::now();
+    let base = dir.join(format!("{}_{}", now.format(TIMESTAMP_FORMAT), sanitize(user_id)));
+    let mut files = Vec::new();
+    match tab.capture_screenshot(Page::CaptureScreenshotFormatOption::Png, None, None, true) {
+        Ok(png) => files.extend(write_capture(base.with_extension("png"), &png)),
+        Err(e) => tracing::warn!("Failed to take screenshot of failed crawl: {}", e),
+    }
+    match tab.get_content() {
+        Ok(html) => files.extend(write_capture(base.with_extension("html"), html.as_bytes())),
+        Err(e) => tracing::warn!("Failed to read page HTML of failed crawl: {}", e),
+    }
+
+    prune(&dir, now, crawler.debug_max_captures, Duration::hours(crawler.debug_max_age_hours));
+    files
+}
+
+fn write_capture(path: PathBuf, contents: &[u8]) -> Option<PathBuf> {
+    match std::fs::write(&path, contents) {
+        Ok(()) => Some(path),
+        Err(e) => {
+            tracing::error!("Failed to write crawler debug capture '{}': {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn prune(dir: &Path, now: DateTime<Utc>, max_captures: usize, max_age: Duration) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::error!("Failed to list crawler debug directory '{}': {}", dir.display(), e);
+            return;
+        }
+    };
+
+    let files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| CAPTURE_EXTENSIONS.contains(&extension))
+        })
+        .collect();
+    let stems: HashSet<String> = files.iter().filter_map(|path| capture_stem(path)).collect();
+    let expired = expired_captures(&stems, now, max_captures, max_age);
+
+    let expired_files = files
+        .into_iter()
+        .filter(|file| capture_stem(file).is_some_and(|stem| expired.contains(&stem)));
+    for file in expired_files {
+        if let Err(e) = std::fs::remove_file(&file) {
+            tracing::warn!("Failed to delete crawler debug capture '{}': {}", file.display(), e);
+        }
+    }
+}
+
+fn capture_stem(path: &Path) -> Option<String> {
+    path.file_stem()?.to_str().map(str::to_string)
+}
+
+/// Captures beyond the newest `max_captures` or older than `max_age`.
+/// Files whose name doesn't start with a capture timestamp are never expired.
+fn expired_captures(stems: &HashSet<String>, now: DateTime<Utc>, max_captures: usize, max_age: Duration) -> HashSet<String> {
+    let mut captures: Vec<(NaiveDateTime, &String)> = stems
+        .iter()
+        .filter_map(|stem| Some((parse_timestamp(stem)?, stem)))
+        .collect();
+    captures.sort_by(|a, b| b.cmp(a));
+
+    let cutoff = (now - max_age).naive_utc();
+    captures
+        .into_iter()
+        .enumerate()
+        .filter(|(index, (taken_at, _))| *index >= max_captures || *taken_at < cutoff)
+        .map(|(_, (_, stem))| stem.clone())
+        .collect()
+}
+
+fn parse_timestamp(stem: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(stem.get(..TIMESTAMP_LEN)?, TIMESTAMP_FORMAT).ok()
+}
+
+/// Keeps user IDs safe to use in a file name.
+fn sanitize(user_id: &str) -> String {
+    user_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expired_captures, parse_timestamp, sanitize, TIMESTAMP_FORMAT};
+    use chrono::{Duration, TimeZone, Utc};
+    use std::collections::HashSet;
+
+    fn stems(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn timestamp_round_trips_through_capture_names() {
+        let taken_at = Utc.with_ymd_and_hms(2026, 3, 1, 7, 30, 5).unwrap();
+        let stem = format!("{}_12345", taken_at.format(TIMESTAMP_FORMAT));
+
+        assert_eq!(stem, "20260301T073005000Z_12345");
+        assert_eq!(parse_timestamp(stem.as_str()), Some(taken_at.naive_utc()));
+    }
+
+    #[test]
+    fn expired_captures_keeps_newest_within_limit() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let captures = stems(&[
+            "20260301T110000000Z_1",
+            "20260301T100000000Z_2",
+            "20260301T090000000Z_3",
+            "notes",
+        ]);
+
+        assert_eq!(
+            expired_captures(&captures, now, 2, Duration::hours(24)),
+            stems(&["20260301T090000000Z_3"])
+        );
+    }
+
+    #[test]
+    fn expired_captures_drops_captures_past_max_age() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 8, 12, 0, 0).unwrap();
+        let captures = stems(&["20260308T110000000Z_1", "20260301T100000000Z_2"]);
+
+        assert_eq!(
+            expired_captures(&captures, now, 10, Duration::hours(168)),
+            stems(&["20260301T100000000Z_2"])
+        );
+    }
+
+    #[test]
+    fn sanitize_replaces_path_characters() {
+        assert_eq!(sanitize("../123 45"), "___123_45");
+    }
+}
@@ -1,2 +1,4 @@
 mod browser;
+mod debug;
 pub mod pages;
+pub mod selectors;
@@ -1,5 +1,9 @@
-use crate::crawler::browser;
+mod embedded;
+
+use crate::config::{get_config, Extraction};
+use crate::crawler::{browser, debug, selectors};
 use crate::models::{NewReview, User};
+use crate::utility::language;
 use anyhow::Result;
 use headless_chrome::Tab;
 use std::thread::sleep;
@@ -11,12 +15,56 @@ struct ReviewText {
 }
 
 static GMAPS_REVIEW_URL: &str = "https://www.google.com/maps/contrib/{}/reviews?hl=en";
+static NO_REVIEW_TEXT: &str = "Review doesn't contain text";
+/// Review pictures are served from subdomains of this host.
+static GOOGLE_PICTURE_HOST: &str = "googleusercontent.com";
+static GOOGLE_PICTURE_CANONICAL_HOST: &str = "lh3.googleusercontent.com";
 
 pub fn get_latest_review_for_user(gmaps_user: &User) -> Result<NewReview> {
     let browser = browser::get(true)?;
     let tab = browser::new_tab(&browser)?;
 
-    let review_url = match open_review_page(&tab, gmaps_user) {
+    read_latest_review(&tab, gmaps_user)
+        .map_err(|e| debug::attach_capture(e, &tab, gmaps_user.gmaps_id.as_str()))
+}
+
+fn read_latest_review(tab: &Tab, gmaps_user: &User) -> Result<NewReview> {
+    let selectors = selectors::current();
+    let capture = match get_config().crawler.extraction {
+        Extraction::Embedded => match embedded::ResponseCapture::register(tab, selectors.embedded.response_urls.clone()) {
+            Ok(capture) => Some(capture),
+            Err(e) => {
+                tracing::warn!("Failed to capture review data responses: {}", e);
+                None
+            }
+        },
+        Extraction::Dom => None,
+    };
+
+    if let Err(err) = open_review_list(tab, gmaps_user) {
+        return Err(anyhow::anyhow!("Failed to open review page for user {}: {}", gmaps_user.gmaps_id.as_str(), err));
+    }
+
+    if let Some(capture) = capture {
+        match embedded::read_latest_review(tab, &capture, &selectors.embedded, gmaps_user) {
+            Ok(review) => {
+                tracing::debug!("Read review from embedded page data");
+                return Ok(review);
+            }
+            Err(e) => tracing::info!(
+                "Reading the review page of user {} as its embedded data could not be parsed: {}",
+                gmaps_user.gmaps_id.as_str(),
+                e
+            ),
+        }
+    }
+
+    scrape_latest_review(tab, gmaps_user)
+}
+
+/// Reads the latest review by opening it and the place it is about.
+fn scrape_latest_review(tab: &Tab, gmaps_user: &User) -> Result<NewReview> {
+    let review_url = match open_first_review(tab, gmaps_user) {
         Ok(val) => val,
         Err(err) => return Err(anyhow::anyhow!("Failed to open review page for user {}: {}", gmaps_user.gmaps_id.as_str(), err)),
     };
@@ -24,17 +72,20 @@ pub fn get_latest_review_for_user(gmaps_user: &User) -> Result<NewReview> {
     let ReviewText {
         text: review_text,
         original_text: original_review_text,
-    } = retrieve_review_text(&tab);
+    } = retrieve_review_text(tab);
     tracing::debug!("Retrieved review text: '{}'", review_text);
 
-    let star_count = retrieve_star_count(&tab)?;
+    let review_language = retrieve_review_language(tab);
+    tracing::debug!("Retrieved review language: {:?}", review_language);
+
+    let star_count = retrieve_star_count(tab)?;
     tracing::debug!("Retrieved star rating: {}", star_count);
 
-    let pictures = retrieve_pictures(&tab, 1)?;
+    let pictures = retrieve_pictures(tab, 1)?;
     let pictures_json = serde_json::to_value(&pictures)?;
     tracing::debug!("Retrieved pictures: {:?}", pictures);
 
-    let place_name = get_place_name(&tab, gmaps_user)?;
+    let place_name = get_place_name(tab, gmaps_user)?;
     tracing::debug!("Retrieved place name: {}", place_name);
 
     Ok(NewReview {
@@ -45,16 +96,18 @@ pub fn get_latest_review_for_user(gmaps_user: &User) -> Result<NewReview> {
         user_id: gmaps_user.id,
         link_en: review_url,
         pictures: pictures_json,
+        language: review_language,
     })
 }
 
-fn open_review_page(tab: &Tab, gmaps_user: &User) -> Result<String> {
+fn open_review_list(tab: &Tab, gmaps_user: &User) -> Result<()> {
     load_review_url(tab, gmaps_user)?;
+    selectors::wait_for_elements(tab, &selectors::current().review.entry)?;
+    Ok(())
+}
 
-    match tab
-        .wait_for_elements_by_xpath(r#"//div[contains(@lang, "en")]"#)?
-        .first()
-    {
+fn open_first_review(tab: &Tab, gmaps_user: &User) -> Result<String> {
+    match selectors::find_elements(tab, &selectors::current().review.entry)?.first() {
         Some(review_element) => {
             review_element.click()?;
             sleep(Duration::from_secs(1));
@@ -71,7 +124,10 @@ fn open_review_page(tab: &Tab, gmaps_user: &User) -> Result<String> {
 }
 
 fn load_review_url(tab: &Tab, gmaps_user: &User) -> Result<()> {
-    let review_url = GMAPS_REVIEW_URL.replace("{}", gmaps_user.gmaps_id.as_ref());
+    let review_url = language::with_display_language(
+        GMAPS_REVIEW_URL.replace("{}", gmaps_user.gmaps_id.as_ref()).as_str(),
+        get_config().crawler.language.as_str(),
+    );
     match tab.navigate_to(review_url.as_str()) {
         Ok(_) => (),
         Err(e) => {
@@ -137,17 +193,16 @@ fn load_single_review_page(tab: &Tab) -> Result<String> {
 fn retrieve_review_text(tab: &Tab) -> ReviewText {
     tracing::debug!("Retrieving review text from page");
 
-    let review_text = match tab.find_element_by_xpath(r#"//div[contains(@lang, "en")]/span"#) {
+    let selectors = selectors::current();
+    let review_text = match selectors::find_element(tab, &selectors.review.text) {
         Ok(elem) => elem
             .get_inner_text()
-            .unwrap_or_else(|_| "Review doesn't contain text".to_string()),
-        Err(_) => "Review doesn't contain text".to_string(),
+            .unwrap_or_else(|_| NO_REVIEW_TEXT.to_string()),
+        Err(_) => NO_REVIEW_TEXT.to_string(),
     };
     tracing::debug!("Retrieved review text element");
 
-    let show_original_button = tab.find_element_by_xpath(
-        r#"//button[contains(@role, "switch")]/span[contains(text(), "original")]"#,
-    );
+    let show_original_button = selectors::find_element(tab, &selectors.review.show_original);
     let original_review_text = match show_original_button {
         Ok(button) => {
             tracing::debug!("Found 'Show original' button, clicking to reveal original text");
@@ -162,11 +217,10 @@ fn retrieve_review_text(tab: &Tab) -> ReviewText {
                 }
             }
             sleep(Duration::from_secs(1));
-            let original_review_text = match tab.find_element_by_xpath(r#"//button[contains(@role, "switch")]/span[contains(text(), "translation")]/../../..//div[@lang]/span"#) {
-                Ok(elem) => elem.get_inner_text().unwrap_or_else(|_| "Review doesn't contain text".to_string()),
-                Err(_) => "Review doesn't contain text".to_string(),
-            };
-            Some(original_review_text)
+            selectors::find_element(tab, &selectors.review.original_text)
+                .ok()
+                .and_then(|elem| elem.get_inner_text().ok())
+                .and_then(|original| normalize_original_text(original, review_text.as_str()))
         }
         Err(_) => None,
     };
@@ -177,10 +231,15 @@ fn retrieve_review_text(tab: &Tab) -> ReviewText {
     }
 }
 
+/// Reads the language of the review text, which is the original once a translation was switched off.
+fn retrieve_review_language(tab: &Tab) -> Option<String> {
+    let element = selectors::find_element(tab, &selectors::current().review.language).ok()?;
+    let code = element.get_attribute_value("lang").ok().flatten()?;
+    language::parse_language_code(code.as_str()).ok()
+}
+
 fn retrieve_star_count(tab: &Tab) -> Result<i32> {
-    let Ok(stars_span) = tab.find_elements_by_xpath(
-        r#"//span[contains(@aria-label, " star")]/span[contains(@class, "google-symbols")]"#,
-    ) else {
+    let Ok(stars_span) = selectors::find_elements(tab, &selectors::current().review.stars) else {
         return Err(anyhow::anyhow!(
             "Failed to find star rating elements for review"
         ));
@@ -212,7 +271,7 @@ fn retrieve_pictures(tab: &Tab, depth: i32) -> Result<Vec<String>> {
     }
 
     tracing::debug!("Retrieving pictures");
-    let picture_elements = match tab.find_elements_by_xpath(r"//div/button[@data-photo-index]") {
+    let picture_elements = match selectors::find_elements(tab, &selectors::current().review.pictures) {
         Ok(elements) => elements,
         Err(e) => {
             tracing::debug!("No picture elements found for review: {e}");
@@ -259,12 +318,7 @@ fn retrieve_pictures(tab: &Tab, depth: i32) -> Result<Vec<String>> {
 
         if let Some(caps) = re.captures(&style) {
             if let Some(url) = caps.get(1) {
-                let clean_url = if let Some(idx) = url.as_str().rfind('=') {
-                    &url.as_str()[..idx] // Remove everything from '=' onwards
-                } else {
-                    url.as_str() // If no '=' is found, use the full URL
-                };
-                pictures.push(clean_url.to_string());
+                pictures.push(url.as_str().to_string());
             } else {
                 tracing::error!("Failed to extract URL from style attribute: {}", style);
             }
@@ -273,21 +327,72 @@ fn retrieve_pictures(tab: &Tab, depth: i32) -> Result<Vec<String>> {
         }
     }
 
-    Ok(pictures)
+    Ok(normalize_pictures(pictures.iter().map(String::as_str)))
 }
 
-fn get_place_name(tab: &Tab, gmaps_user: &User) -> Result<String> {
-    let place_details_button =
-        match tab.find_element_by_xpath(r#"//div[contains(@jsaction, "placeNameHeader")]"#) {
-            Ok(button) => button,
-            Err(e) => {
-                return Err(anyhow::anyhow!(
-                    "Failed to find place details button for user {}: {}",
-                    gmaps_user.gmaps_id.as_str(),
-                    e
-                ));
-            }
+/// Pictures as they are stored, whichever way the review was read: Google-hosted pictures without
+/// their size options on one host, each once even when listed in several sizes or on several hosts.
+fn normalize_pictures<'a>(urls: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut pictures = Vec::new();
+    for url in urls {
+        let Ok(mut parsed) = reqwest::Url::parse(clean_picture_url(url).as_str()) else {
+            continue;
         };
+        if !parsed.host_str().is_some_and(|host| host.ends_with(GOOGLE_PICTURE_HOST)) {
+            continue;
+        }
+        if parsed.host_str().is_some_and(is_numbered_picture_host)
+            && parsed.set_host(Some(GOOGLE_PICTURE_CANONICAL_HOST)).is_err()
+        {
+            continue;
+        }
+
+        let picture = parsed.to_string();
+        if !pictures.contains(&picture) {
+            pictures.push(picture);
+        }
+    }
+    pictures
+}
+
+/// Whether the host is one of `lh3.googleusercontent.com`, `lh5.googleusercontent.com` and so on, which serve the same pictures.
+fn is_numbered_picture_host(host: &str) -> bool {
+    host.strip_suffix(GOOGLE_PICTURE_HOST)
+        .and_then(|prefix| prefix.strip_prefix("lh"))
+        .and_then(|prefix| prefix.strip_suffix('.'))
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Original text as it is stored, whichever way the review was read: without a distinct original
+/// the review wasn't translated.
+fn normalize_original_text(original: String, text: &str) -> Option<String> {
+    Some(original).filter(|original| !original.trim().is_empty() && original.trim() != text.trim())
+}
+
+/// Place name as it is stored, whichever way the review was read.
+fn normalize_place_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Drops the size options Google appends after the last '='.
+fn clean_picture_url(url: &str) -> String {
+    match url.rfind('=') {
+        Some(idx) => url[..idx].to_string(),
+        None => url.to_string(),
+    }
+}
+
+fn get_place_name(tab: &Tab, gmaps_user: &User) -> Result<String> {
+    let place_details_button = match selectors::find_element(tab, &selectors::current().review.place_header) {
+        Ok(button) => button,
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "Failed to find place details button for user {}: {}",
+                gmaps_user.gmaps_id.as_str(),
+                e
+            ));
+        }
+    };
 
     match place_details_button.click() {
         Ok(_) => (),
@@ -326,7 +431,7 @@ fn get_place_name_from_url(url: &str) -> Option<String> {
                 .unwrap_or_else(|_| "Unknown Place".into())
                 .to_string();
             name = name.replace('+', " ");
-            Some(name)
+            Some(normalize_place_name(name.as_str()))
         }
         None => Some("Unknown Place".to_string()),
     }
@@ -334,7 +439,17 @@ fn get_place_name_from_url(url: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::get_place_name_from_url;
+    use super::{get_place_name_from_url, normalize_original_text, normalize_pictures};
+
+    #[test]
+    fn normalize_original_text_keeps_only_a_distinct_original() {
+        assert_eq!(
+            normalize_original_text("Texte original".to_string(), "Original text"),
+            Some("Texte original".to_string())
+        );
+        assert_eq!(normalize_original_text(" Same text\n".to_string(), "Same text"), None);
+        assert_eq!(normalize_original_text("  ".to_string(), "Same text"), None);
+    }
 
     #[test]
     fn get_place_name_from_url_decodes_encoded_characters() {
@@ -360,6 +475,23 @@ mod tests {
         assert_eq!(get_place_name_from_url(url), Some("AT&T Store".to_string()));
     }
 
+    #[test]
+    fn normalize_pictures_keeps_each_google_picture_once() {
+        let pictures = normalize_pictures([
+            "https://lh5.googleusercontent.com/p/abc=w100-h100",
+            "https://lh3.googleusercontent.com/p/abc=w400",
+            "https://www.google.com/maps/contrib/123",
+            "https://lh5.googleusercontent.com/p/def",
+        ]);
+        assert_eq!(
+            pictures,
+            vec![
+                "https://lh3.googleusercontent.com/p/abc".to_string(),
+                "https://lh3.googleusercontent.com/p/def".to_string()
+            ]
+        );
+    }
+
     #[test]
     fn get_place_name_from_url_returns_none_when_at_segment_is_missing() {
         let url = "https://www.google.com/maps/place/Cafe+Noir";
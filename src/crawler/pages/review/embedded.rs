@@ -0,0 +1,315 @@
+use super::{get_place_name_from_url, normalize_original_text, normalize_pictures, normalize_place_name, NO_REVIEW_TEXT};
+use crate::crawler::selectors::{EmbeddedSelectors, PathChain};
+use crate::models::{NewReview, User};
+use crate::utility::language;
+use anyhow::Result;
+use headless_chrome::Tab;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// Google prefixes its JSON with this so it can't be included as a script.
+static XSSI_PREFIX: &str = ")]}'";
+static RESPONSE_HANDLER: &str = "embedded-review-data";
+static APP_STATE_EXPRESSION: &str = "JSON.stringify(window.APP_INITIALIZATION_STATE ?? null)";
+/// Links outside Google Maps, e.g. to the place's website, are not review links.
+static GMAPS_URL_PREFIX: &str = "https://www.google.com/maps/";
+
+/// Keeps the bodies of review data responses received by the tab while it exists.
+pub struct ResponseCapture<'a> {
+    tab: &'a Tab,
+    bodies: Arc<Mutex<Vec<String>>>,
+}
+
+impl<'a> ResponseCapture<'a> {
+    /// Starts capturing responses whose URL contains one of `response_urls`; register before navigating.
+    pub fn register(tab: &'a Tab, response_urls: Vec<String>) -> Result<Self> {
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let captured = bodies.clone();
+        tab.register_response_handling(
+            RESPONSE_HANDLER,
+            Box::new(move |params, get_body| {
+                let url = params.response.url;
+                if !response_urls.iter().any(|pattern| url.contains(pattern.as_str())) {
+                    return;
+                }
+                match get_body() {
+                    Ok(body) if body.base_64_encoded => tracing::debug!("Skipping binary review data response {}", url),
+                    Ok(body) => {
+                        if let Ok(mut bodies) = captured.lock() {
+                            bodies.push(body.body);
+                        }
+                    }
+                    Err(e) => tracing::debug!("Failed to read review data response {}: {}", url, e),
+                }
+            }),
+        )?;
+        Ok(ResponseCapture { tab, bodies })
+    }
+
+    fn bodies(&self) -> Vec<String> {
+        self.bodies.lock().map(|bodies| bodies.clone()).unwrap_or_default()
+    }
+}
+
+impl Drop for ResponseCapture<'_> {
+    fn drop(&mut self) {
+        let _ = self.tab.deregister_response_handling(RESPONSE_HANDLER);
+    }
+}
+
+/// Reads the newest review from the captured responses and the state embedded in the page.
+pub fn read_latest_review(
+    tab: &Tab,
+    capture: &ResponseCapture,
+    selectors: &EmbeddedSelectors,
+    gmaps_user: &User,
+) -> Result<NewReview> {
+    let mut payloads = capture.bodies();
+    match tab.evaluate(APP_STATE_EXPRESSION, false) {
+        Ok(state) => payloads.extend(state.value.and_then(|value| value.as_str().map(str::to_string))),
+        Err(e) => tracing::debug!("Failed to read embedded page state: {}", e),
+    }
+    tracing::debug!("Parsing {} embedded review data payloads", payloads.len());
+
+    latest_review(&payloads, selectors, gmaps_user.id)
+}
+
+fn latest_review(payloads: &[String], selectors: &EmbeddedSelectors, user_id: i32) -> Result<NewReview> {
+    let mut reviews = Vec::new();
+    for data in payloads.iter().filter_map(|payload| parse_payload(payload)) {
+        collect_reviews(&data, selectors, user_id, &mut reviews);
+    }
+
+    // Reviews are listed newest first, so the first one wins when timestamps are equal.
+    reviews
+        .into_iter()
+        .reduce(|newest, review| if review.0 > newest.0 { review } else { newest })
+        .map(|(_, review)| review)
+        .ok_or_else(|| anyhow::anyhow!("No review found in embedded page data"))
+}
+
+fn parse_payload(payload: &str) -> Option<Value> {
+    let payload = payload.trim_start();
+    let payload = payload.strip_prefix(XSSI_PREFIX).unwrap_or(payload);
+    serde_json::from_str(payload).ok().map(expand_nested)
+}
+
+/// The page state holds further responses as prefixed JSON strings.
+fn expand_nested(value: Value) -> Value {
+    match value {
+        Value::String(text) if text.trim_start().starts_with(XSSI_PREFIX) => {
+            parse_payload(text.as_str()).unwrap_or(Value::String(text))
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(expand_nested).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key, expand_nested(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+fn collect_reviews(value: &Value, selectors: &EmbeddedSelectors, user_id: i32, reviews: &mut Vec<(i64, NewReview)>) {
+    match value {
+        Value::Array(items) => {
+            reviews.extend(review_from_entry(value, selectors, user_id));
+            for item in items {
+                collect_reviews(item, selectors, user_id, reviews);
+            }
+        }
+        Value::Object(fields) => {
+            for item in fields.values() {
+                collect_reviews(item, selectors, user_id, reviews);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// An array is taken as a review when it has a rating, a place name, a review time and a review link at the
+/// configured paths. Its place name, original text and pictures are normalized like those read from the
+/// rendered page, so switching between both doesn't make the stored review look edited.
+fn review_from_entry(entry: &Value, selectors: &EmbeddedSelectors, user_id: i32) -> Option<(i64, NewReview)> {
+    let stars = resolve(entry, &selectors.stars, |value| value.as_i64().filter(|stars| (1..=5).contains(stars)))?;
+    let display_name = resolve(entry, &selectors.place_name, non_empty_string)?;
+    let timestamp = resolve(entry, &selectors.timestamp, |value| value.as_i64().filter(|timestamp| *timestamp > 0))?;
+    let link = resolve(entry, &selectors.link, |value| {
+        non_empty_string(value).filter(|link| link.starts_with(GMAPS_URL_PREFIX))
+    })?;
+    let place_name = get_place_name_from_url(link.as_str()).unwrap_or_else(|| normalize_place_name(display_name.as_str()));
+    let text = resolve(entry, &selectors.text, non_empty_string).unwrap_or_else(|| NO_REVIEW_TEXT.to_string());
+    let original_text = resolve(entry, &selectors.original_text, non_empty_string)
+        .and_then(|original| normalize_original_text(original, text.as_str()));
+    let pictures = resolve(entry, &selectors.pictures, |value| Some(picture_urls(value))).unwrap_or_default();
+    let language = resolve(entry, &selectors.language, |value| {
+        value.as_str().and_then(|code| language::parse_language_code(code).ok())
+    });
+
+    Some((
+        timestamp,
+        NewReview {
+            place_name,
+            text,
+            original_text,
+            stars: stars as i32,
+            user_id,
+            link_en: link,
+            pictures: serde_json::to_value(pictures).ok()?,
+            language,
+        },
+    ))
+}
+
+/// Value read from the first path of the chain that leads to one.
+fn resolve<T>(entry: &Value, chain: &PathChain, read: impl Fn(&Value) -> Option<T>) -> Option<T> {
+    chain
+        .iter()
+        .find_map(|path| path.iter().try_fold(entry, |value, index| value.get(*index)).and_then(&read))
+}
+
+fn non_empty_string(value: &Value) -> Option<String> {
+    value.as_str().filter(|text| !text.trim().is_empty()).map(str::to_string)
+}
+
+fn picture_urls(value: &Value) -> Vec<String> {
+    let mut urls = Vec::new();
+    collect_strings(value, &mut urls);
+    normalize_pictures(urls)
+}
+
+fn collect_strings<'a>(value: &'a Value, strings: &mut Vec<&'a str>) {
+    match value {
+        Value::String(text) => strings.push(text.as_str()),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, strings)),
+        Value::Object(fields) => fields.values().for_each(|item| collect_strings(item, strings)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::latest_review;
+    use crate::crawler::pages::review::{get_place_name_from_url, normalize_pictures};
+    use crate::crawler::selectors::EmbeddedSelectors;
+    use serde_json::json;
+
+    fn selectors() -> EmbeddedSelectors {
+        EmbeddedSelectors {
+            response_urls: vec!["/listentitiesreviews".to_string()],
+            stars: vec![vec![4]],
+            text: vec![vec![3]],
+            original_text: vec![vec![6, 0]],
+            timestamp: vec![vec![5]],
+            place_name: vec![vec![1, 0], vec![1, 1, 0]],
+            link: vec![vec![2]],
+            pictures: vec![vec![7]],
+            language: vec![vec![8]],
+        }
+    }
+
+    fn payload(entries: serde_json::Value) -> String {
+        format!(")]}}'\n{}", json!([null, ["header", entries]]))
+    }
+
+    #[test]
+    fn latest_review_picks_newest_entry() {
+        let entries = json!([
+            ["id1", ["Old Place"], "https://www.google.com/maps/reviews/data=old", "Old text", 3, 1_700_000_000_000_i64],
+            [
+                "id2",
+                [null, ["Café de Flore"]],
+                "https://www.google.com/maps/reviews/data=new",
+                "Translated text",
+                5,
+                1_750_000_000_000_i64,
+                ["Texte original"],
+                [[["https://lh5.googleusercontent.com/p/abc=w100-h100"]], ["https://lh5.googleusercontent.com/p/abc=w400"]],
+                "fr"
+            ],
+        ]);
+
+        let review = latest_review(&[payload(entries)], &selectors(), 7).unwrap();
+
+        assert_eq!(review.place_name, "Café de Flore");
+        assert_eq!(review.stars, 5);
+        assert_eq!(review.text, "Translated text");
+        assert_eq!(review.original_text.as_deref(), Some("Texte original"));
+        assert_eq!(review.link_en, "https://www.google.com/maps/reviews/data=new");
+        assert_eq!(review.pictures, json!(["https://lh3.googleusercontent.com/p/abc"]));
+        assert_eq!(review.language.as_deref(), Some("fr"));
+        assert_eq!(review.user_id, 7);
+    }
+
+    #[test]
+    fn latest_review_reads_responses_nested_in_page_state() {
+        let inner = payload(json!([["id1", ["Place"], "https://www.google.com/maps/reviews/data=1", null, 4, 1_i64]]));
+        let state = json!([[null, inner]]).to_string();
+
+        let review = latest_review(&[state], &selectors(), 1).unwrap();
+
+        assert_eq!(review.link_en, "https://www.google.com/maps/reviews/data=1");
+        assert_eq!(review.text, "Review doesn't contain text");
+        assert_eq!(review.pictures, json!([]));
+    }
+
+    #[test]
+    fn latest_review_needs_time_and_review_link() {
+        let entries = json!([
+            ["id1", ["Place"], "https://www.google.com/maps/reviews/data=1", "Text", 9, 1_i64],
+            ["id2", ["Place"], "https://www.google.com/maps/reviews/data=2", "Text", 4],
+            ["id3", ["Place"], "https://example.com/menu", "Text", 4, 1_i64],
+            ["id4", ["Place", null, "place-id"], null, "Text", 4, 1_i64],
+        ]);
+
+        assert!(latest_review(&[payload(entries), "<html>".to_string()], &selectors(), 1).is_err());
+    }
+
+    #[test]
+    fn latest_review_drops_an_original_equal_to_the_text() {
+        let entries = json!([[
+            "id1",
+            ["Place"],
+            "https://www.google.com/maps/reviews/data=1",
+            "Same text",
+            4,
+            1_i64,
+            ["Same text "],
+        ]]);
+
+        let review = latest_review(&[payload(entries)], &selectors(), 1).unwrap();
+
+        assert_eq!(review.original_text, None);
+    }
+
+    #[test]
+    fn embedded_review_matches_the_rendered_page() {
+        // The same review as read from the rendered page: the place name comes from the place URL,
+        // the pictures from the background images of the picture buttons.
+        let place_url = "https://www.google.com/maps/place/Caf%C3%A9+de+Flore/@48.854,2.333,17z/data=!4m8";
+        let rendered_pictures = normalize_pictures([
+            "https://lh5.googleusercontent.com/p/abc=w300-h225-p-k-no",
+            "https://lh5.googleusercontent.com/p/def=w300-h225-p-k-no",
+        ]);
+        let entries = json!([[
+            "id1",
+            ["Café  de Flore "],
+            place_url,
+            "Text",
+            5,
+            1_i64,
+            null,
+            [
+                ["https://lh3.googleusercontent.com/p/abc=w100", "https://lh5.googleusercontent.com/p/abc=s0"],
+                ["https://lh5.googleusercontent.com/p/def=w1200"],
+                "https://www.google.com/maps/contrib/123",
+            ],
+        ]]);
+
+        let review = latest_review(&[payload(entries)], &selectors(), 1).unwrap();
+
+        assert_eq!(Some(review.place_name), get_place_name_from_url(place_url));
+        assert_eq!(review.pictures, json!(rendered_pictures));
+    }
+}
@@ -1,5 +1,7 @@
-use crate::crawler::browser;
+use crate::config::get_config;
+use crate::crawler::{browser, debug, selectors};
 use crate::models::NewUser;
+use crate::utility::language;
 use anyhow::Result;
 
 pub static GMAPS_USER_URL: &str = "https://www.google.com/maps/contrib/{}/reviews?hl=en";
@@ -8,9 +10,13 @@ pub fn get_user_from_id(user_id: &str) -> Result<NewUser> {
     let browser = browser::get(true)?;
     let tab = browser::new_tab(&browser)?;
 
-    open_user_page(&tab, user_id)?;
+    read_user(&tab, user_id).map_err(|e| debug::attach_capture(e, &tab, user_id))
+}
+
+fn read_user(tab: &headless_chrome::Tab, user_id: &str) -> Result<NewUser> {
+    open_user_page(tab, user_id)?;
 
-    let name_element = match tab.find_element_by_xpath(r"//button[contains(@jsaction, 'pane.profile-stats.showStats')][contains(@class, 'fontHeadlineLarge')]") {
+    let name_element = match selectors::find_element(tab, &selectors::current().user.name) {
         Ok(e) => e,
         Err(e) => return Err(anyhow::anyhow!("Failed to find user name element for user {user_id}: {e}")),
     };
@@ -25,7 +31,10 @@ pub fn get_user_from_id(user_id: &str) -> Result<NewUser> {
 }
 
 fn open_user_page(tab: &headless_chrome::Tab, user_id: &str) -> Result<()> {
-    let user_url = GMAPS_USER_URL.replace("{}", user_id);
+    let user_url = language::with_display_language(
+        GMAPS_USER_URL.replace("{}", user_id).as_str(),
+        get_config().crawler.language.as_str(),
+    );
     match tab.navigate_to(&user_url) {
         Ok(_) => (),
         Err(e) => {
@@ -0,0 +1,291 @@
+use crate::config::get_config;
+use headless_chrome::{Element, Tab};
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// Format version of selector files this build understands.
+pub const SELECTORS_VERSION: u32 = 1;
+static DEFAULT_SELECTORS: &str = include_str!("selectors.toml");
+
+static SELECTORS: RwLock<Option<Arc<Selectors>>> = RwLock::new(None);
+
+/// XPath selectors tried in order until one matches.
+pub type Chain = Vec<String>;
+/// Index paths into embedded page data tried in order until one resolves.
+pub type PathChain = Vec<Vec<usize>>;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Selectors {
+    pub version: u32,
+    pub consent: ConsentSelectors,
+    pub user: UserSelectors,
+    pub review: ReviewSelectors,
+    pub embedded: EmbeddedSelectors,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConsentSelectors {
+    pub accept_button: Chain,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserSelectors {
+    pub name: Chain,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReviewSelectors {
+    pub entry: Chain,
+    pub text: Chain,
+    pub show_original: Chain,
+    pub original_text: Chain,
+    pub stars: Chain,
+    pub pictures: Chain,
+    pub place_header: Chain,
+    pub language: Chain,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmbeddedSelectors {
+    pub response_urls: Chain,
+    pub stars: PathChain,
+    pub text: PathChain,
+    pub original_text: PathChain,
+    pub timestamp: PathChain,
+    pub place_name: PathChain,
+    pub link: PathChain,
+    pub pictures: PathChain,
+    pub language: PathChain,
+}
+
+impl Selectors {
+    fn chains(&self) -> [(&'static str, &Chain); 11] {
+        [
+            ("consent.accept_button", &self.consent.accept_button),
+            ("user.name", &self.user.name),
+            ("review.entry", &self.review.entry),
+            ("review.text", &self.review.text),
+            ("review.show_original", &self.review.show_original),
+            ("review.original_text", &self.review.original_text),
+            ("review.stars", &self.review.stars),
+            ("review.pictures", &self.review.pictures),
+            ("review.place_header", &self.review.place_header),
+            ("review.language", &self.review.language),
+            ("embedded.response_urls", &self.embedded.response_urls),
+        ]
+    }
+
+    fn path_chains(&self) -> [(&'static str, &PathChain); 8] {
+        let embedded = &self.embedded;
+        [
+            ("embedded.stars", &embedded.stars),
+            ("embedded.text", &embedded.text),
+            ("embedded.original_text", &embedded.original_text),
+            ("embedded.timestamp", &embedded.timestamp),
+            ("embedded.place_name", &embedded.place_name),
+            ("embedded.link", &embedded.link),
+            ("embedded.pictures", &embedded.pictures),
+            ("embedded.language", &embedded.language),
+        ]
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.version != SELECTORS_VERSION {
+            errors.push(format!(
+                "unsupported version {}, expected {}",
+                self.version, SELECTORS_VERSION
+            ));
+        }
+        for (name, chain) in self.chains() {
+            if chain.is_empty() {
+                errors.push(format!("{} must list at least one selector", name));
+            } else if chain.iter().any(|xpath| xpath.trim().is_empty()) {
+                errors.push(format!("{} must not contain empty selectors", name));
+            }
+        }
+        for (name, chain) in self.path_chains() {
+            if chain.is_empty() {
+                errors.push(format!("{} must list at least one path", name));
+            } else if chain.iter().any(Vec::is_empty) {
+                errors.push(format!("{} must not contain empty paths", name));
+            }
+        }
+        errors
+    }
+}
+
+/// Reads the built-in selectors with the fields of the override file, if any, replacing theirs.
+pub fn load(path: Option<&Path>) -> Result<Selectors, String> {
+    let overrides = match path {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(contents) => Some(contents),
+            Err(e) => return Err(format!("failed to read '{}': {}", path.display(), e)),
+        },
+        None => None,
+    };
+    parse(overrides.as_deref())
+}
+
+fn parse(overrides: Option<&str>) -> Result<Selectors, String> {
+    let mut table: toml::Table = toml::from_str(DEFAULT_SELECTORS).map_err(|e| format!("built-in selectors: {}", e))?;
+    if let Some(overrides) = overrides {
+        let overrides: toml::Table = toml::from_str(overrides).map_err(|e| e.to_string())?;
+        merge(&mut table, overrides);
+    }
+
+    let selectors: Selectors = toml::Value::Table(table).try_into().map_err(|e: toml::de::Error| e.to_string())?;
+    let errors = selectors.validate();
+    if errors.is_empty() {
+        Ok(selectors)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+fn merge(table: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (table.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(value)) => merge(existing, value),
+            (_, value) => {
+                table.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The selectors of the current configuration, loaded on first use.
+/// Falls back to the built-in selectors when the override file became unreadable since it was validated.
+pub fn current() -> Arc<Selectors> {
+    if let Some(selectors) = SELECTORS.read().ok().and_then(|selectors| selectors.clone()) {
+        return selectors;
+    }
+
+    let config = get_config();
+    let selectors = match load(config.crawler.selectors_file.as_deref().map(Path::new)) {
+        Ok(selectors) => selectors,
+        Err(e) => {
+            tracing::error!("Failed to load crawler selectors, using the built-in ones: {}", e);
+            parse(None).expect("built-in selectors are valid")
+        }
+    };
+    let selectors = Arc::new(selectors);
+    if let Ok(mut current) = SELECTORS.write() {
+        *current = Some(selectors.clone());
+    }
+    selectors
+}
+
+/// Drops the current selectors so the next crawl reads them again.
+pub fn reset() {
+    if let Ok(mut current) = SELECTORS.write() {
+        *current = None;
+    }
+}
+
+/// First element matched by the chain.
+pub fn find_element<'a>(tab: &'a Tab, chain: &[String]) -> anyhow::Result<Element<'a>> {
+    first_match(chain, |xpath| tab.find_element_by_xpath(xpath))
+}
+
+/// Elements of the first selector in the chain that matches any.
+pub fn find_elements<'a>(tab: &'a Tab, chain: &[String]) -> anyhow::Result<Vec<Element<'a>>> {
+    first_match(chain, |xpath| non_empty(tab.find_elements_by_xpath(xpath)?))
+}
+
+/// Like `find_element`, waiting up to the tab's default timeout for each selector.
+pub fn wait_for_element<'a>(tab: &'a Tab, chain: &[String]) -> anyhow::Result<Element<'a>> {
+    first_match(chain, |xpath| tab.wait_for_xpath(xpath))
+}
+
+/// Like `find_elements`, waiting up to the tab's default timeout for each selector.
+pub fn wait_for_elements<'a>(tab: &'a Tab, chain: &[String]) -> anyhow::Result<Vec<Element<'a>>> {
+    first_match(chain, |xpath| non_empty(tab.wait_for_elements_by_xpath(xpath)?))
+}
+
+fn non_empty<T>(elements: Vec<T>) -> anyhow::Result<Vec<T>> {
+    if elements.is_empty() {
+        return Err(anyhow::anyhow!("no elements found"));
+    }
+    Ok(elements)
+}
+
+fn first_match<T>(chain: &[String], find: impl Fn(&str) -> anyhow::Result<T>) -> anyhow::Result<T> {
+    let mut errors = Vec::new();
+    for (index, xpath) in chain.iter().enumerate() {
+        match find(xpath) {
+            Ok(found) => {
+                if index > 0 {
+                    tracing::debug!("Matched fallback selector {} after {} failed: {}", xpath, index, errors.join("; "));
+                }
+                return Ok(found);
+            }
+            Err(e) => errors.push(format!("'{}': {}", xpath, e)),
+        }
+    }
+    Err(anyhow::anyhow!("No selector matched ({})", errors.join("; ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{first_match, parse, SELECTORS_VERSION};
+
+    #[test]
+    fn built_in_selectors_are_valid() {
+        let selectors = parse(None).unwrap();
+
+        assert_eq!(selectors.version, SELECTORS_VERSION);
+        assert_eq!(selectors.review.pictures, vec!["//div/button[@data-photo-index]".to_string()]);
+    }
+
+    #[test]
+    fn built_in_review_text_selectors_stay_in_the_review() {
+        let review = parse(None).unwrap().review;
+
+        for chain in [&review.entry, &review.text, &review.show_original, &review.original_text, &review.language] {
+            assert!(chain.iter().all(|selector| selector.contains("div[@data-review-id]")), "{:?}", chain);
+        }
+    }
+
+    #[test]
+    fn overrides_replace_only_the_given_chains() {
+        let selectors = parse(Some(
+            r#"
+            [review]
+            stars = ["//span[@role='img']/span", "//span[contains(@aria-label, ' star')]/span"]
+            "#,
+        ))
+        .unwrap();
+        let defaults = parse(None).unwrap();
+
+        assert_eq!(selectors.review.stars.len(), 2);
+        assert_eq!(selectors.review.text, defaults.review.text);
+        assert_eq!(selectors.user.name, defaults.user.name);
+    }
+
+    #[test]
+    fn invalid_overrides_are_rejected() {
+        assert!(parse(Some("version = 2")).unwrap_err().contains("unsupported version 2"));
+        assert!(parse(Some("[user]\nname = []")).unwrap_err().contains("user.name must list at least one selector"));
+        assert!(parse(Some("[review]\nstar = ['//span']")).is_err());
+    }
+
+    #[test]
+    fn first_match_falls_back_in_order() {
+        let chain = vec!["//a".to_string(), "//b".to_string(), "//c".to_string()];
+        let found = first_match(&chain, |xpath| match xpath {
+            "//a" => Err(anyhow::anyhow!("not found")),
+            other => Ok(other.to_string()),
+        });
+        assert_eq!(found.unwrap(), "//b");
+
+        let error = first_match(&chain, |_| Err::<(), _>(anyhow::anyhow!("not found"))).unwrap_err();
+        assert!(error.to_string().contains("'//c': not found"));
+    }
+}
@@ -1,7 +1,11 @@
 use crate::discord::commands::*;
+use crate::provider;
+use crate::utility;
 use anyhow::{Error, Result};
+use async_trait::async_trait;
 use poise::serenity_prelude as serenity;
 use poise::serenity_prelude::InteractionType;
+use tracing::Instrument;
 
 pub async fn build(token: String) -> Result<serenity::Client> {
     let intents = serenity::GatewayIntents::non_privileged();
@@ -9,14 +13,19 @@ pub async fn build(token: String) -> Result<serenity::Client> {
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
             commands: vec![
+                delivery::delivery_command(),
                 follow::follow_user(),
                 followed::followed_command(),
                 latest::latest_review(),
                 lookup::lookup_user(),
+                subscribe::subscribe_user(),
+                subscriptions::subscriptions_command(),
+                unsubscribe::unsubscribe_user(),
             ],
-            event_handler: |ctx, event, framework, data| {
+            event_handler: |ctx, event, _framework, _data| {
                 Box::pin(async move {
-                    log_interaction(ctx, event, framework, data);
+                    utility::health::record_gateway_event(event);
+                    handle_component_interaction(ctx, event).await;
                     Ok(())
                 })
             },
@@ -39,7 +48,7 @@ pub async fn build(token: String) -> Result<serenity::Client> {
         .build();
 
     let client = serenity::ClientBuilder::new(&token, intents)
-        .framework(framework)
+        .framework(TracedFramework { inner: framework })
         .await;
 
     match client {
@@ -51,23 +60,105 @@ pub async fn build(token: String) -> Result<serenity::Client> {
     }
 }
 
-fn log_interaction(
-    _ctx: &serenity::Context,
-    event: &serenity::FullEvent,
-    _framework: poise::FrameworkContext<'_, (), Error>,
-    _data: &(),
-) {
-    if let serenity::FullEvent::InteractionCreate { interaction } = event {
-        if interaction.kind() == InteractionType::Command {
-            let interaction = interaction.as_command().unwrap();
-            tracing::info!(
-                "Slash command: user='{}({})', guild='{}', channel='{}', command='{}'",
-                interaction.user.name,
-                interaction.user.id,
-                interaction.guild_id.unwrap().get(),
-                interaction.channel.clone().unwrap().id.get(),
-                interaction.data.name
-            );
+/// Runs every slash command inside a span naming the command and where it was invoked.
+struct TracedFramework {
+    inner: poise::Framework<(), Error>,
+}
+
+#[async_trait]
+impl serenity::Framework for TracedFramework {
+    async fn init(&mut self, client: &serenity::Client) {
+        self.inner.init(client).await;
+    }
+
+    async fn dispatch(&self, ctx: serenity::Context, event: serenity::FullEvent) {
+        match slash_command_span(&event) {
+            Some(span) => {
+                span.in_scope(|| tracing::info!("Slash command"));
+                self.inner.dispatch(ctx, event).instrument(span).await;
+            }
+            None => self.inner.dispatch(ctx, event).await,
+        }
+    }
+}
+
+fn slash_command_span(event: &serenity::FullEvent) -> Option<tracing::Span> {
+    let serenity::FullEvent::InteractionCreate { interaction } = event else {
+        return None;
+    };
+    if interaction.kind() != InteractionType::Command {
+        return None;
+    }
+    let command = interaction.as_command()?;
+
+    Some(tracing::info_span!(
+        "slash_command",
+        command = %command.data.name,
+        user = %command.user.name,
+        user_id = command.user.id.get(),
+        guild_id = command.guild_id.map(|id| id.get()).unwrap_or_default(),
+        channel_id = command.channel_id.get()
+    ))
+}
+
+async fn handle_component_interaction(ctx: &serenity::Context, event: &serenity::FullEvent) {
+    let serenity::FullEvent::InteractionCreate { interaction } = event else {
+        return;
+    };
+    let Some(component) = interaction.as_message_component() else {
+        return;
+    };
+    let Some((review_id, original)) =
+        utility::embed::parse_review_body_toggle_id(component.data.custom_id.as_str())
+    else {
+        return;
+    };
+
+    let response = match provider::review::get_review_with_user_by_id(review_id) {
+        Some(review_with_user) => {
+            let language = notification_language(component, review_with_user.user.id);
+            let localized = utility::language::localized_review(&review_with_user, language.as_deref());
+            serenity::CreateInteractionResponse::UpdateMessage(
+                serenity::CreateInteractionResponseMessage::new()
+                    .embeds(utility::embed::get_review_embeds(&localized, original))
+                    .components(utility::embed::get_review_components(&localized, original)),
+            )
+        }
+        None => serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .content("❌ This review is no longer available.")
+                .ephemeral(true),
+        ),
+    };
+
+    if let Err(e) = component.create_response(&ctx.http, response).await {
+        tracing::error!("Failed to respond to review toggle for review {}: {}", review_id, e);
+    }
+}
+
+/// Display language of the follow or subscription the toggled notification was sent for.
+fn notification_language(component: &serenity::ComponentInteraction, user_id: i32) -> Option<String> {
+    if component.guild_id.is_none() {
+        return match provider::subscription::get_subscription(user_id, component.user.id.to_string()) {
+            Ok(subscription) => subscription.and_then(|subscription| subscription.language),
+            Err(e) => {
+                tracing::error!("Failed to get subscription for review toggle: {}", e);
+                None
+            }
+        };
+    }
+
+    // Notifications in per-user threads and forum posts belong to the follow of the parent channel.
+    let parent_id = component.channel.as_ref().and_then(|channel| channel.parent_id);
+    for channel_id in std::iter::once(component.channel_id).chain(parent_id) {
+        match provider::following::get_following_in_channel(user_id, channel_id.to_string()) {
+            Ok(Some(following)) => return following.language,
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!("Failed to get follow for review toggle: {}", e);
+                return None;
+            }
         }
     }
+    None
 }
@@ -0,0 +1,225 @@
+use crate::background::digest::DeliveryMode;
+use crate::discord::commands::{ack, target_channel_id, CommandCtx};
+use crate::models::ChannelSettings;
+use crate::provider;
+use anyhow::Result;
+use chrono::{NaiveTime, Utc};
+use chrono_tz::Tz;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum DigestDay {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl DigestDay {
+    fn number_from_monday(self) -> i32 {
+        match self {
+            DigestDay::Monday => 1,
+            DigestDay::Tuesday => 2,
+            DigestDay::Wednesday => 3,
+            DigestDay::Thursday => 4,
+            DigestDay::Friday => 5,
+            DigestDay::Saturday => 6,
+            DigestDay::Sunday => 7,
+        }
+    }
+}
+
+/// Choose whether reviews in this channel are posted immediately or as a digest.
+#[poise::command(
+    slash_command,
+    rename = "delivery",
+    default_member_permissions = "MANAGE_WEBHOOKS",
+    required_bot_permissions = "MANAGE_WEBHOOKS"
+)]
+pub async fn delivery_command<U: Sync>(
+    ctx: CommandCtx<'_, U>,
+    #[description = "How new reviews are delivered"] mode: DeliveryMode,
+    #[description = "Digest time as HH:MM (hourly digests only use the minutes)"] time: Option<String>,
+    #[description = "IANA timezone, e.g. Europe/Amsterdam"] timezone: Option<String>,
+    #[description = "Day of the week for weekly digests"] day: Option<DigestDay>,
+) -> Result<()> {
+    ack(&ctx).await;
+
+    let channel_id = target_channel_id(&ctx).await.to_string();
+    let current = match provider::digest::get_channel_settings(channel_id.as_str()) {
+        Ok(settings) => settings,
+        Err(e) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("❌ Failed to retrieve delivery settings: {}", e))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let settings = match build_channel_settings(channel_id, mode, time, timezone, day, current) {
+        Ok(settings) => settings,
+        Err(message) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("❌ {}", message))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let content = match provider::digest::save_channel_settings(&settings) {
+        Ok(saved) => format!("✅ {}", describe_channel_settings(&saved)),
+        Err(e) => format!("❌ Failed to save delivery settings: {}", e),
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn build_channel_settings(
+    channel_id: String,
+    mode: DeliveryMode,
+    time: Option<String>,
+    timezone: Option<String>,
+    day: Option<DigestDay>,
+    current: Option<ChannelSettings>,
+) -> Result<ChannelSettings, String> {
+    let digest_time = match time {
+        Some(t) => NaiveTime::parse_from_str(t.trim(), "%H:%M")
+            .map_err(|_| format!("Invalid time '{}', expected HH:MM", t))?,
+        None => current
+            .as_ref()
+            .map(|c| c.digest_time)
+            .unwrap_or_else(|| NaiveTime::from_hms_opt(9, 0, 0).unwrap()),
+    };
+
+    let timezone = match timezone {
+        Some(tz) => {
+            let tz = tz.trim().to_string();
+            tz.parse::<Tz>()
+                .map_err(|_| format!("Unknown timezone '{}'", tz))?;
+            tz
+        }
+        None => current
+            .as_ref()
+            .map(|c| c.timezone.clone())
+            .unwrap_or_else(|| "UTC".to_string()),
+    };
+
+    let digest_weekday = match day {
+        Some(d) => d.number_from_monday(),
+        None => current.as_ref().map(|c| c.digest_weekday).unwrap_or(1),
+    };
+
+    Ok(ChannelSettings {
+        channel_id,
+        delivery_mode: mode.as_str().to_string(),
+        digest_time,
+        digest_weekday,
+        timezone,
+        // Start counting from now so the first digest only contains reviews queued from here on.
+        last_digest_at: Some(Utc::now().naive_utc()),
+    })
+}
+
+fn describe_channel_settings(settings: &ChannelSettings) -> String {
+    let time = settings.digest_time.format("%H:%M");
+    match DeliveryMode::parse(settings.delivery_mode.as_str()) {
+        Some(DeliveryMode::Hourly) => format!(
+            "Reviews will be posted as an hourly digest at minute {}",
+            settings.digest_time.format("%M")
+        ),
+        Some(DeliveryMode::Daily) => format!(
+            "Reviews will be posted as a daily digest at {} ({})",
+            time, settings.timezone
+        ),
+        Some(DeliveryMode::Weekly) => format!(
+            "Reviews will be posted as a weekly digest on {} at {} ({})",
+            weekday_name(settings.digest_weekday),
+            time,
+            settings.timezone
+        ),
+        Some(DeliveryMode::Immediate) | None => "Reviews will be posted immediately".to_string(),
+    }
+}
+
+fn weekday_name(weekday: i32) -> &'static str {
+    match weekday {
+        2 => "Tuesday",
+        3 => "Wednesday",
+        4 => "Thursday",
+        5 => "Friday",
+        6 => "Saturday",
+        7 => "Sunday",
+        _ => "Monday",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_channel_settings, describe_channel_settings, DigestDay};
+    use crate::background::digest::DeliveryMode;
+    use chrono::NaiveTime;
+
+    #[test]
+    fn build_channel_settings_uses_defaults() {
+        let settings = build_channel_settings("1".to_string(), DeliveryMode::Daily, None, None, None, None).unwrap();
+        assert_eq!(settings.delivery_mode, "daily");
+        assert_eq!(settings.digest_time, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        assert_eq!(settings.timezone, "UTC");
+        assert_eq!(settings.digest_weekday, 1);
+    }
+
+    #[test]
+    fn build_channel_settings_keeps_previous_values() {
+        let previous = build_channel_settings(
+            "1".to_string(),
+            DeliveryMode::Weekly,
+            Some("18:30".to_string()),
+            Some("Europe/Amsterdam".to_string()),
+            Some(DigestDay::Friday),
+            None,
+        )
+        .unwrap();
+        let settings =
+            build_channel_settings("1".to_string(), DeliveryMode::Daily, None, None, None, Some(previous)).unwrap();
+        assert_eq!(settings.digest_time, NaiveTime::from_hms_opt(18, 30, 0).unwrap());
+        assert_eq!(settings.timezone, "Europe/Amsterdam");
+        assert_eq!(settings.digest_weekday, 5);
+    }
+
+    #[test]
+    fn build_channel_settings_rejects_invalid_input() {
+        assert!(build_channel_settings("1".to_string(), DeliveryMode::Daily, Some("25:00".to_string()), None, None, None).is_err());
+        assert!(build_channel_settings("1".to_string(), DeliveryMode::Daily, None, Some("Nowhere/City".to_string()), None, None).is_err());
+    }
+
+    #[test]
+    fn describe_channel_settings_mentions_weekday_and_timezone() {
+        let settings = build_channel_settings(
+            "1".to_string(),
+            DeliveryMode::Weekly,
+            Some("08:15".to_string()),
+            Some("UTC".to_string()),
+            Some(DigestDay::Sunday),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            describe_channel_settings(&settings),
+            "Reviews will be posted as a weekly digest on Sunday at 08:15 (UTC)"
+        );
+    }
+}
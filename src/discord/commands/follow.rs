@@ -1,8 +1,12 @@
 use crate::background::worker;
-use crate::discord::commands::{ack, CommandCtx};
+use crate::discord::commands::{ack, display_language_changed, target_channel, CommandCtx};
 use crate::provider::*;
+use crate::utility;
+use crate::models::Following;
+use crate::utility::mention::{parse_user_mentions, MentionUpdate, Mentions};
 use anyhow::Result;
-use poise::serenity_prelude::{CreateWebhook, WebhookId};
+use poise::serenity_prelude::{ChannelType, CreateWebhook, GuildChannel, Role, WebhookId};
+use tracing::Instrument;
 
 /// Start or stop following a user in the current channel.
 #[poise::command(
@@ -11,23 +15,64 @@ use poise::serenity_prelude::{CreateWebhook, WebhookId};
     default_member_permissions = "MANAGE_WEBHOOKS",
     required_bot_permissions = "MANAGE_WEBHOOKS"
 )]
+#[allow(clippy::too_many_arguments)]
 pub async fn follow_user<U: Sync>(
     ctx: CommandCtx<'_, U>,
     #[description = "The ID of the user to follow"] id: String,
     #[description = "Enable or disable following"] enabled: bool,
     original: Option<bool>,
+    #[description = "Role to mention on new reviews"] mention_role: Option<Role>,
+    #[description = "Users to mention on new reviews, e.g. @alice @bob"] mention_users: Option<String>,
+    #[description = "Mention @here on new reviews"] mention_here: Option<bool>,
+    #[description = "Remove the stored mentions before applying the given ones"] clear_mentions: Option<bool>,
+    #[description = "Post each user's reviews in a dedicated thread"] thread: Option<bool>,
+    #[description = "Language for links and for showing reviews as written, e.g. de or pt-BR; none to remove"] language: Option<String>,
 ) -> Result<()> {
     ack(&ctx).await;
 
-    handle_follow_switch(id, enabled, original.unwrap_or(true), ctx).await;
+    let language = match language.as_deref().map(utility::language::parse_display_language).transpose() {
+        Ok(language) => language,
+        Err(e) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("❌ {}", e))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let mentions = MentionUpdate {
+        role_id: mention_role.map(|role| role.id.to_string()),
+        user_ids: mention_users.as_deref().map(parse_user_mentions),
+        here: mention_here,
+        clear: clear_mentions.unwrap_or(false),
+    };
+
+    let options = FollowOptions {
+        original: original.unwrap_or(true),
+        mentions,
+        thread_per_user: thread,
+        language,
+    };
+    handle_follow_switch(id, enabled, options, ctx).await;
 
     Ok(())
 }
 
+struct FollowOptions {
+    original: bool,
+    mentions: MentionUpdate,
+    thread_per_user: Option<bool>,
+    /// `Some(None)` removes the display language of an existing follow.
+    language: Option<Option<String>>,
+}
+
 async fn handle_follow_switch<U: Sync>(
     gmaps_id: String,
     enable: bool,
-    original: bool,
+    options: FollowOptions,
     ctx: CommandCtx<'_, U>,
 ) {
     let user_id = match user::gmaps_user_id_to_db_id(gmaps_id.as_ref()) {
@@ -44,33 +89,7 @@ async fn handle_follow_switch<U: Sync>(
         }
     };
 
-    let is_followed = following::is_user_followed_in_channel(user_id, ctx.channel_id().to_string());
-
-    if enable {
-        handle_enable(is_followed, user_id, original, ctx).await;
-    } else {
-        handle_disable(is_followed, user_id, ctx).await;
-    }
-}
-
-async fn handle_enable(
-    is_followed: bool,
-    user_id: i32,
-    original: bool,
-    ctx: CommandCtx<'_, impl Sync>,
-) {
-    if is_followed {
-        let _ = ctx
-            .send(
-                poise::CreateReply::default()
-                    .content("⚠️ User is already being followed in this channel")
-                    .ephemeral(true),
-            )
-            .await;
-        return;
-    }
-
-    let channel = match ctx.guild_channel().await {
+    let channel = match target_channel(&ctx).await {
         Some(c) => c,
         None => {
             let _ = ctx
@@ -84,6 +103,39 @@ async fn handle_enable(
         }
     };
 
+    let existing = match following::get_following_in_channel(user_id, channel.id.to_string()) {
+        Ok(existing) => existing,
+        Err(e) => {
+            let _ = ctx
+                .send(
+                    poise::CreateReply::default()
+                        .content(format!("❌ Failed to look up follow: {}", e))
+                        .ephemeral(true),
+                )
+                .await;
+            return;
+        }
+    };
+
+    if enable {
+        handle_enable(existing, user_id, options, channel, ctx).await;
+    } else {
+        handle_disable(existing.is_some(), user_id, channel.id.to_string(), ctx).await;
+    }
+}
+
+async fn handle_enable(
+    existing: Option<Following>,
+    user_id: i32,
+    options: FollowOptions,
+    channel: GuildChannel,
+    ctx: CommandCtx<'_, impl Sync>,
+) {
+    if let Some(existing) = existing {
+        handle_update(&existing, &options, ctx).await;
+        return;
+    }
+
     let webhook = match channel
         .create_webhook(
             &ctx.http(),
@@ -104,11 +156,17 @@ async fn handle_enable(
         }
     };
 
+    // Every review already becomes its own post in a forum, so per-user threads don't apply there.
+    let forum = channel.kind == ChannelType::Forum;
     match following::follow_user_in_channel(
         user_id,
-        ctx.channel_id().to_string(),
-        original,
+        channel.id.to_string(),
+        options.original,
         webhook.id.to_string(),
+        &options.mentions.apply(&Mentions::default()),
+        options.thread_per_user.unwrap_or(false) && !forum,
+        forum,
+        options.language.flatten(),
     ) {
         Ok(following) => {
             let _ = ctx
@@ -119,9 +177,7 @@ async fn handle_enable(
                 )
                 .await;
 
-            tokio::task::spawn(
-                async move { worker::channel_started_following_user(following).await },
-            );
+            tokio::task::spawn(worker::channel_started_following_user(following).in_current_span());
         }
         Err(e) => {
             let _ = ctx
@@ -135,7 +191,56 @@ async fn handle_enable(
     }
 }
 
-async fn handle_disable(is_followed: bool, user_id: i32, ctx: CommandCtx<'_, impl Sync>) {
+/// Applies the given options to a follow that already exists.
+async fn handle_update(existing: &Following, options: &FollowOptions, ctx: CommandCtx<'_, impl Sync>) {
+    let mut replies = Vec::new();
+
+    if !options.mentions.is_empty() {
+        let mentions = options.mentions.apply(&Mentions::from_following(existing));
+        replies.push(match following::update_mentions(existing.followed_user_id, existing.channel_id.clone(), &mentions) {
+            Ok(()) => "✅ Updated mentions for user in this channel".to_string(),
+            Err(e) => format!("❌ Failed to update mentions: {}", e),
+        });
+    }
+
+    if let Some(thread_per_user) = options.thread_per_user {
+        replies.push(if existing.forum {
+            "⚠️ Threads don't apply in forum channels, every review is already its own post".to_string()
+        } else {
+            match following::update_thread_per_user(existing.id, thread_per_user) {
+                Ok(()) if thread_per_user => "✅ Reviews of this user are now posted in their thread".to_string(),
+                Ok(()) => "✅ Reviews of this user are now posted in the channel".to_string(),
+                Err(e) => format!("❌ Failed to update thread mode: {}", e),
+            }
+        });
+    }
+
+    if let Some(language) = &options.language {
+        replies.push(match following::update_language(existing.id, language.clone()) {
+            Ok(()) => display_language_changed(language.as_deref()),
+            Err(e) => format!("❌ Failed to update language: {}", e),
+        });
+    }
+
+    if replies.is_empty() {
+        replies.push("⚠️ User is already being followed in this channel".to_string());
+    }
+
+    let _ = ctx
+        .send(
+            poise::CreateReply::default()
+                .content(replies.join("\n"))
+                .ephemeral(true),
+        )
+        .await;
+}
+
+async fn handle_disable(
+    is_followed: bool,
+    user_id: i32,
+    channel_id: String,
+    ctx: CommandCtx<'_, impl Sync>,
+) {
     if !is_followed {
         let _ = ctx
             .send(
@@ -146,7 +251,7 @@ async fn handle_disable(is_followed: bool, user_id: i32, ctx: CommandCtx<'_, imp
             .await;
         return;
     }
-    match following::unfollow_user_in_channel(user_id, ctx.channel_id().to_string()) {
+    match following::unfollow_user_in_channel(user_id, channel_id) {
         Ok(following) => {
             match ctx
                 .http()
@@ -1,4 +1,4 @@
-use crate::discord::commands::{ack, CommandCtx};
+use crate::discord::commands::{ack, target_channel_id, CommandCtx};
 use crate::models::User;
 use crate::provider;
 use anyhow::Result;
@@ -14,7 +14,7 @@ pub async fn followed_command<U: Sync>(ctx: CommandCtx<'_, U>) -> Result<()> {
     ack(&ctx).await;
 
     let users = match provider::following::get_users_followed_in_channel(
-        ctx.channel_id().get().to_string(),
+        target_channel_id(&ctx).await.get().to_string(),
     ) {
         Ok(u) => u,
         Err(e) => {
@@ -19,9 +19,12 @@ pub async fn latest_review<U: Sync>(
 
     match review::get_latest_review_for_user_gmaps_id(id.as_str()) {
         Some(review_with_user) => {
+            let reply = utility::embed::get_review_embeds(&review_with_user, original.unwrap_or(true))
+                .into_iter()
+                .fold(CreateReply::default(), CreateReply::embed);
             ctx.send(
-                CreateReply::default()
-                    .embed(utility::embed::get_review_embed(
+                reply
+                    .components(utility::embed::get_review_components(
                         &review_with_user,
                         original.unwrap_or(true),
                     ))
@@ -1,12 +1,40 @@
 use anyhow::Error;
+use poise::serenity_prelude::{Channel, ChannelId, ChannelType, GuildChannel};
 
+pub mod delivery;
 pub mod follow;
 pub mod followed;
 pub mod latest;
 pub mod lookup;
+pub mod subscribe;
+pub mod subscriptions;
+pub mod unsubscribe;
 
 type CommandCtx<'a, U> = poise::Context<'a, U, Error>;
 
+/// Resolves the channel follows apply to; commands used inside a forum post target the forum itself.
+async fn target_channel<U: Sync>(ctx: &CommandCtx<'_, U>) -> Option<GuildChannel> {
+    let channel = ctx.guild_channel().await?;
+    if !matches!(channel.kind, ChannelType::PublicThread | ChannelType::PrivateThread) {
+        return Some(channel);
+    }
+
+    let Some(parent_id) = channel.parent_id else {
+        return Some(channel);
+    };
+    match parent_id.to_channel(ctx.http()).await {
+        Ok(Channel::Guild(parent)) if parent.kind == ChannelType::Forum => Some(parent),
+        _ => Some(channel),
+    }
+}
+
+async fn target_channel_id<U: Sync>(ctx: &CommandCtx<'_, U>) -> ChannelId {
+    target_channel(ctx)
+        .await
+        .map(|channel| channel.id)
+        .unwrap_or_else(|| ctx.channel_id())
+}
+
 async fn ack<U: Sync>(ctx: &CommandCtx<'_, U>) {
     match ctx.defer().await {
         Ok(_) => {}
@@ -15,3 +43,14 @@ async fn ack<U: Sync>(ctx: &CommandCtx<'_, U>) {
         }
     }
 }
+
+/// Confirms a changed display language, stating what it affects.
+fn display_language_changed(language: Option<&str>) -> String {
+    match language {
+        Some(language) => format!(
+            "✅ Review links now open in '{}' and reviews written in it are shown as written",
+            language
+        ),
+        None => "✅ Removed the display language".to_string(),
+    }
+}
@@ -0,0 +1,81 @@
+use crate::background::worker;
+use crate::discord::commands::{ack, display_language_changed, CommandCtx};
+use crate::provider::*;
+use crate::utility;
+use anyhow::Result;
+use tracing::Instrument;
+
+/// Receive new reviews from a user in your direct messages.
+#[poise::command(slash_command, rename = "subscribe")]
+pub async fn subscribe_user<U: Sync>(
+    ctx: CommandCtx<'_, U>,
+    #[description = "The ID of the user to subscribe to"] id: String,
+    original: Option<bool>,
+    #[description = "Language for links and for showing reviews as written, e.g. de or pt-BR; none to remove"] language: Option<String>,
+) -> Result<()> {
+    ack(&ctx).await;
+
+    let language = match language.as_deref().map(utility::language::parse_display_language).transpose() {
+        Ok(language) => language,
+        Err(e) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("❌ {}", e))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let Some(user_id) = user::gmaps_user_id_to_db_id(id.as_ref()) else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Unable to retrieve specified user")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let discord_user_id = ctx.author().id.to_string();
+    if subscription::is_subscribed(user_id, discord_user_id.clone()) {
+        let content = match language {
+            Some(language) => match subscription::update_language(user_id, discord_user_id, language.clone()) {
+                Ok(()) => display_language_changed(language.as_deref()),
+                Err(e) => format!("❌ Failed to update language: {}", e),
+            },
+            None => "⚠️ You are already subscribed to this user".to_string(),
+        };
+        ctx.send(
+            poise::CreateReply::default()
+                .content(content)
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    match subscription::subscribe(user_id, discord_user_id, original.unwrap_or(true), language.flatten()) {
+        Ok(subscription) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content("✅ You will now receive new reviews from this user by direct message")
+                    .ephemeral(true),
+            )
+            .await?;
+
+            tokio::task::spawn(worker::user_subscribed(subscription).in_current_span());
+        }
+        Err(e) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("❌ Failed to subscribe: {}", e))
+                    .ephemeral(true),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
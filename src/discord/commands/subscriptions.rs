@@ -0,0 +1,79 @@
+use crate::discord::commands::{ack, CommandCtx};
+use crate::models::User;
+use crate::provider;
+use anyhow::Result;
+
+/// Gets a list of users you receive new reviews from by direct message.
+#[poise::command(slash_command, rename = "subscriptions")]
+pub async fn subscriptions_command<U: Sync>(ctx: CommandCtx<'_, U>) -> Result<()> {
+    ack(&ctx).await;
+
+    let users = match provider::subscription::get_users_subscribed_by(ctx.author().id.to_string()) {
+        Ok(u) => u,
+        Err(e) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("❌ Failed to retrieve subscriptions: {}", e))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let Some(content) = format_subscribed_users(&users) else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("ℹ️ You are not subscribed to any users.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn format_subscribed_users(users: &[User]) -> Option<String> {
+    if users.is_empty() {
+        return None;
+    }
+
+    let mut content = String::from("📋 **Your Subscriptions:**\n");
+    for user in users {
+        content.push_str(&format!("- {} (`{}`)\n", user.name, user.gmaps_id));
+    }
+    Some(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_subscribed_users;
+    use crate::models::User;
+
+    #[test]
+    fn format_subscribed_users_returns_none_for_empty_list() {
+        assert!(format_subscribed_users(&[]).is_none());
+    }
+
+    #[test]
+    fn format_subscribed_users_lists_users() {
+        let users = vec![User {
+            id: 1,
+            gmaps_id: "abc123".to_string(),
+            name: "Alice".to_string(),
+        }];
+
+        assert_eq!(
+            format_subscribed_users(&users).as_deref(),
+            Some("📋 **Your Subscriptions:**\n- Alice (`abc123`)\n")
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use crate::discord::commands::{ack, CommandCtx};
+use crate::provider::*;
+use anyhow::Result;
+
+/// Stop receiving new reviews from a user in your direct messages.
+#[poise::command(slash_command, rename = "unsubscribe")]
+pub async fn unsubscribe_user<U: Sync>(
+    ctx: CommandCtx<'_, U>,
+    #[description = "The ID of the user to unsubscribe from"] id: String,
+) -> Result<()> {
+    ack(&ctx).await;
+
+    let Some(user_id) = user::gmaps_user_id_to_db_id(id.as_ref()) else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Unable to retrieve specified user")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let discord_user_id = ctx.author().id.to_string();
+    if !subscription::is_subscribed(user_id, discord_user_id.clone()) {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("⚠️ You are not subscribed to this user")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let content = match subscription::unsubscribe(user_id, discord_user_id) {
+        Ok(_) => "✅ Unsubscribed from user".to_string(),
+        Err(e) => format!("❌ Failed to unsubscribe: {}", e),
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use crate::config::get_config;
+use crate::crawler::pages::user::GMAPS_USER_URL;
+use crate::models::ReviewWithUser;
+use crate::provider;
+use crate::utility;
+use axum::extract::{Path, Query};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+/// How many entries a feed lists.
+const FEED_ENTRY_LIMIT: i64 = 50;
+static FEED_EXTENSION: &str = ".atom";
+static ATOM_CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/feeds/user/{file}", get(user_feed))
+        .route("/feeds/channel/{file}", get(channel_feed))
+}
+
+#[derive(Deserialize)]
+struct FeedQuery {
+    token: Option<String>,
+}
+
+/// Metadata of the feed document itself.
+pub struct FeedInfo {
+    pub id: String,
+    pub title: String,
+    pub self_path: String,
+    pub alternate_url: Option<String>,
+}
+
+async fn user_feed(Path(file): Path<String>) -> Response {
+    let Some(gmaps_id) = file.strip_suffix(FEED_EXTENSION).map(str::to_string) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let lookup_id = gmaps_id.clone();
+    let reviews = tokio::task::spawn_blocking(move || {
+        provider::review::get_recent_reviews_for_gmaps_id(lookup_id.as_str(), FEED_ENTRY_LIMIT)
+    })
+        .await
+        .ok()
+        .flatten();
+    let Some(reviews) = reviews else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let Some(user) = reviews.first().map(|review| review.user.clone()) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let info = FeedInfo {
+        id: format!("urn:gmaps-review-notif:user:{}", gmaps_id),
+        title: format!("Google Maps reviews by {}", user.name),
+        self_path: format!("/feeds/user/{}", file),
+        alternate_url: Some(GMAPS_USER_URL.replace("{}", gmaps_id.as_str())),
+    };
+    atom_response(&info, &reviews)
+}
+
+/// Channel feeds reveal which users a channel follows, so they are only served with the configured token.
+async fn channel_feed(Path(file): Path<String>, Query(query): Query<FeedQuery>) -> Response {
+    if !feed_token_matches(get_config().http.feed_token.as_deref(), query.token.as_deref()) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let Some(channel_id) = file.strip_suffix(FEED_EXTENSION).map(str::to_string) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let lookup_id = channel_id.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let followings = provider::following::get_followings_in_channel(lookup_id.clone()).ok()?;
+        let reviews = provider::review::get_recent_reviews_in_channel(lookup_id.as_str(), FEED_ENTRY_LIMIT)?;
+        Some((followings, reviews))
+    })
+        .await
+        .ok()
+        .flatten();
+    let Some((followings, reviews)) = result else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    if followings.is_empty() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let info = FeedInfo {
+        id: format!("urn:gmaps-review-notif:channel:{}", channel_id),
+        title: format!("Google Maps reviews followed in channel {}", channel_id),
+        self_path: format!("/feeds/channel/{}", file),
+        alternate_url: None,
+    };
+    atom_response(&info, &reviews)
+}
+
+/// Compares in constant time, so the token can't be guessed from response times.
+fn feed_token_matches(configured: Option<&str>, given: Option<&str>) -> bool {
+    let (Some(configured), Some(given)) = (configured, given) else {
+        return false;
+    };
+    configured.len() == given.len()
+        && configured
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+fn atom_response(info: &FeedInfo, reviews: &[ReviewWithUser]) -> Response {
+    let config = get_config();
+    let feed = build_atom_feed(
+        info,
+        reviews,
+        config.star_text.as_str(),
+        config.http.public_url.as_deref(),
+        Utc::now(),
+    );
+    ([(header::CONTENT_TYPE, ATOM_CONTENT_TYPE)], feed).into_response()
+}
+
+/// Renders reviews, newest first, as an Atom document.
+///
+/// Without a public URL the feed has no self link, since it can't know how it is reached.
+pub fn build_atom_feed(
+    info: &FeedInfo,
+    reviews: &[ReviewWithUser],
+    star_text: &str,
+    public_url: Option<&str>,
+    now: DateTime<Utc>,
+) -> String {
+    let updated = reviews
+        .iter()
+        .map(|review| review.review.found_at.and_utc())
+        .max()
+        .unwrap_or(now);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(format!("  <id>{}</id>\n", escape_xml(info.id.as_str())).as_str());
+    xml.push_str(format!("  <title>{}</title>\n", escape_xml(info.title.as_str())).as_str());
+    xml.push_str(format!("  <updated>{}</updated>\n", updated.to_rfc3339()).as_str());
+    if let Some(public_url) = public_url {
+        let self_url = format!("{}{}", public_url.trim_end_matches('/'), info.self_path);
+        xml.push_str(format!("  <link rel=\"self\" href=\"{}\"/>\n", escape_xml(self_url.as_str())).as_str());
+    }
+    if let Some(alternate_url) = &info.alternate_url {
+        xml.push_str(format!("  <link rel=\"alternate\" href=\"{}\"/>\n", escape_xml(alternate_url)).as_str());
+    }
+    xml.push_str("  <generator>gmaps_review_notif</generator>\n");
+
+    for review in reviews {
+        xml.push_str(build_atom_entry(review, star_text).as_str());
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn build_atom_entry(review_with_user: &ReviewWithUser, star_text: &str) -> String {
+    let review = &review_with_user.review;
+    let user = &review_with_user.user;
+    let user_url = GMAPS_USER_URL.replace("{}", user.gmaps_id.as_str());
+    let stars = star_text.repeat(review.stars.try_into().unwrap_or_default());
+    let found_at = review.found_at.and_utc().to_rfc3339();
+
+    let mut entry = String::from("  <entry>\n");
+    entry.push_str(format!("    <id>urn:gmaps-review-notif:review:{}</id>\n", review.id).as_str());
+    entry.push_str(format!("    <title>{} {}</title>\n", escape_xml(review.place_name.as_str()), escape_xml(stars.as_str())).as_str());
+    entry.push_str(format!("    <updated>{}</updated>\n", found_at).as_str());
+    entry.push_str(format!("    <published>{}</published>\n", found_at).as_str());
+    entry.push_str(
+        format!(
+            "    <author>\n      <name>{}</name>\n      <uri>{}</uri>\n    </author>\n",
+            escape_xml(user.name.as_str()),
+            escape_xml(user_url.as_str())
+        )
+            .as_str(),
+    );
+    let alternate = review.link_en.as_deref().unwrap_or(user_url.as_str());
+    entry.push_str(format!("    <link rel=\"alternate\" href=\"{}\"/>\n", escape_xml(alternate)).as_str());
+    for picture in utility::embed::collect_valid_pictures(&review.pictures) {
+        entry.push_str(format!("    <link rel=\"enclosure\" href=\"{}\"/>\n", escape_xml(picture)).as_str());
+    }
+    entry.push_str(format!("    <content type=\"text\">{}</content>\n", escape_xml(review.text.as_str())).as_str());
+    entry.push_str("  </entry>\n");
+    entry
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_atom_feed, escape_xml, feed_token_matches, FeedInfo};
+    use crate::notifier::tests::sample_event;
+    use chrono::{TimeZone, Utc};
+
+    fn info() -> FeedInfo {
+        FeedInfo {
+            id: "urn:gmaps-review-notif:user:gmaps-42".to_string(),
+            title: "Google Maps reviews by Alice".to_string(),
+            self_path: "/feeds/user/gmaps-42.atom".to_string(),
+            alternate_url: None,
+        }
+    }
+
+    #[test]
+    fn build_atom_feed_renders_entries() {
+        let review = sample_event().review;
+        let now = Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap();
+        let feed = build_atom_feed(&info(), &[review], "*", Some("https://reviews.example.com/"), now);
+
+        assert!(feed.contains("<updated>2026-01-15T09:30:00+00:00</updated>\n  <link rel=\"self\" href=\"https://reviews.example.com/feeds/user/gmaps-42.atom\"/>"));
+        assert!(feed.contains("<id>urn:gmaps-review-notif:review:7</id>"));
+        assert!(feed.contains("<title>Cafe ****</title>"));
+        assert!(feed.contains("<name>Alice</name>"));
+        assert!(feed.contains("<link rel=\"alternate\" href=\"https://example.com/review\"/>"));
+        assert!(feed.contains("<link rel=\"enclosure\" href=\"https://img/1\"/>\n    <link rel=\"enclosure\" href=\"https://img/2\"/>"));
+        assert!(feed.contains("<content type=\"text\">Great coffee</content>"));
+    }
+
+    #[test]
+    fn build_atom_feed_without_reviews_uses_current_time() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap();
+        let feed = build_atom_feed(&info(), &[], "*", None, now);
+
+        assert!(feed.contains("<updated>2026-02-01T00:00:00+00:00</updated>"));
+        assert!(!feed.contains("rel=\"self\""));
+        assert!(!feed.contains("<entry>"));
+    }
+
+    #[test]
+    fn escape_xml_escapes_markup() {
+        assert_eq!(escape_xml("<a href=\"x\">Tom's & Jerry's</a>"), "&lt;a href=&quot;x&quot;&gt;Tom&apos;s &amp; Jerry&apos;s&lt;/a&gt;");
+    }
+
+    #[test]
+    fn feed_token_matches_only_the_configured_token() {
+        assert!(feed_token_matches(Some("secret"), Some("secret")));
+        assert!(!feed_token_matches(Some("secret"), Some("secreT")));
+        assert!(!feed_token_matches(Some("secret"), None));
+        assert!(!feed_token_matches(None, Some("secret")));
+        assert!(!feed_token_matches(None, None));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::config::get_config;
+use crate::provider;
+use crate::provider::db::DbProvider;
+use crate::utility::{health, metrics};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use chrono::Utc;
+use std::time::Duration;
+
+/// How long readiness waits for a database connection before reporting the database as down.
+const DATABASE_TIMEOUT: Duration = Duration::from_secs(2);
+static METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(metrics_endpoint))
+}
+
+/// Answers as long as the process is alive.
+async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// Reports each readiness check on its own line and fails when any of them does.
+async fn readyz() -> Response {
+    let database = tokio::task::spawn_blocking(is_database_reachable)
+        .await
+        .unwrap_or(false);
+    let gateway = health::is_gateway_connected();
+    let schedule = get_config().scheduler.new_review_fetch_interval.clone();
+    let review_check = !health::is_review_check_stale(schedule.as_str(), Utc::now());
+    let review_check_state = if !review_check {
+        "stale"
+    } else if health::is_review_check_running() {
+        "running"
+    } else {
+        "ok"
+    };
+
+    let last_review_check = health::last_review_check()
+        .map(|last| last.to_rfc3339())
+        .unwrap_or_else(|| "never".to_string());
+    let body = format!(
+        "database: {}\ndiscord gateway: {}\nreview check: {} (last completed: {})\n",
+        if database { "ok" } else { "unreachable" },
+        if gateway { "ok" } else { "disconnected" },
+        review_check_state,
+        last_review_check
+    );
+
+    let status = if database && gateway && review_check {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, body).into_response()
+}
+
+async fn metrics_endpoint() -> Response {
+    if let Err(e) = tokio::task::spawn_blocking(refresh_database_metrics).await {
+        tracing::error!("Failed to refresh database metrics: {}", e);
+    }
+    ([(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)], metrics::render()).into_response()
+}
+
+fn is_database_reachable() -> bool {
+    match DbProvider::global().pool.get_timeout(DATABASE_TIMEOUT) {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::warn!("Database is not reachable for readiness check: {}", e);
+            false
+        }
+    }
+}
+
+/// Updates the gauges that are read from the database rather than recorded as things happen.
+fn refresh_database_metrics() {
+    let state = DbProvider::global().pool.state();
+    metrics::set_db_pool_connections(state.idle_connections, state.connections - state.idle_connections);
+
+    match count_follows() {
+        Ok(counts) => metrics::set_follow_counts(&counts),
+        Err(e) => tracing::error!("Failed to count follows for metrics: {}", e),
+    }
+}
+
+fn count_follows() -> anyhow::Result<metrics::FollowCounts> {
+    Ok(metrics::FollowCounts {
+        followed_users: provider::following::get_amount_of_users_followed()?,
+        channel_follows: provider::following::get_amount_of_followings()?,
+        subscriptions: provider::subscription::get_amount_of_subscriptions()?,
+        sinks: provider::sink::get_amount_of_sinks()?,
+    })
+}
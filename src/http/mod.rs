@@ -0,0 +1,29 @@
+use axum::Router;
+use tokio::net::TcpListener;
+
+pub mod feed;
+pub mod health;
+pub mod redirect;
+
+pub fn router() -> Router {
+    Router::new()
+        .merge(feed::router())
+        .merge(health::router())
+        .merge(redirect::router())
+}
+
+/// Serves the HTTP endpoints until the process exits.
+pub async fn serve(bind_address: &str) {
+    let listener = match TcpListener::bind(bind_address).await {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!("Failed to bind HTTP server to {}: {}", bind_address, e);
+            return;
+        }
+    };
+
+    tracing::info!("HTTP server listening on {}", bind_address);
+    if let Err(e) = axum::serve(listener, router()).await {
+        tracing::error!("HTTP server error: {}", e);
+    }
+}
@@ -0,0 +1,24 @@
+use crate::provider;
+use crate::utility::shorten::builtin::decode_code;
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::routing::get;
+use axum::Router;
+
+/// Redirects links created by the built-in shortener.
+pub fn router() -> Router {
+    Router::new().route("/s/{code}", get(short_link))
+}
+
+async fn short_link(Path(code): Path<String>) -> Response {
+    let Some(id) = decode_code(code.as_str()) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    match tokio::task::spawn_blocking(move || provider::short_link::get_short_link(id)).await {
+        Ok(Ok(Some(link))) => Redirect::permanent(link.url.as_str()).into_response(),
+        Ok(Ok(None)) => StatusCode::NOT_FOUND.into_response(),
+        _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
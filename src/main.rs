@@ -1,40 +1,112 @@
 extern crate core;
 
-use crate::background::worker;
+use crate::background::{digest, dry_run, reload, worker};
+use crate::cli::{Cli, Command, ConfigCommand};
 use crate::config::get_config;
+use clap::Parser;
 use crate::provider::db::DbProvider;
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use poise::serenity_prelude::Client;
+use std::path::{Path, PathBuf};
 use tokio_cron_scheduler::{Job, JobScheduler};
-use tracing_subscriber::FmtSubscriber;
+use uuid::Uuid;
 
 mod background;
+mod cli;
 mod config;
 mod crawler;
 mod discord;
+mod http;
 mod models;
+mod notifier;
 mod provider;
 mod schema;
 mod utility;
 
 pub const DIESEL_MIGRATIONS: EmbeddedMigrations = embed_migrations!();
+const DIGEST_CHECK_INTERVAL: &str = "0 * * * * *";
 
 #[tokio::main]
 async fn main() {
+    let cli = Cli::parse();
     load_env().await;
+    let config_path = config::resolve_path(cli.config.as_deref());
+    let command = cli.command.unwrap_or(Command::Run);
+    if let Command::Config(ConfigCommand::Check) = command {
+        let valid = cli::check_config(config_path.as_deref());
+        std::process::exit(if valid { 0 } else { 1 });
+    }
+    load_config(config_path.as_deref());
 
-    let subscriber = FmtSubscriber::builder()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .finish();
-    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
-    tracing_log::LogTracer::init().expect("failed to init logger");
+    let telemetry = utility::telemetry::init(&get_config().logging);
 
-    init_db().await;
+    match command {
+        Command::Run => {
+            init_db().await;
+            run(config_path).await
+        }
+        Command::Migrate => match run_migrations() {
+            Ok(0) => println!("No pending migrations"),
+            Ok(applied) => println!("Applied {} migrations", applied),
+            Err(e) => exit_with_error("Migration failed", e),
+        },
+        Command::CrawlUser { gmaps_id } => {
+            if let Err(e) = cli::crawl_user(gmaps_id.as_str()) {
+                exit_with_error("Crawl failed", e);
+            }
+        }
+        Command::CheckNow { dry_run } => {
+            init_db().await;
+            if dry_run {
+                dry_run_check().await
+            } else {
+                check_now().await
+            }
+        }
+        Command::ListFollows => {
+            init_db().await;
+            if let Err(e) = cli::list_follows() {
+                exit_with_error("Listing follows failed", e);
+            }
+        }
+        Command::Healthcheck { live } => {
+            if let Err(e) = cli::healthcheck(live).await {
+                exit_with_error("Healthcheck failed", e);
+            }
+        }
+        Command::Config(ConfigCommand::Check) => unreachable!("handled before loading the configuration"),
+        Command::Sink(command) => {
+            init_db().await;
+            if let Err(e) = cli::run_sink_command(command) {
+                exit_with_error("Sink command failed", e);
+            }
+        }
+    }
+    telemetry.shutdown();
+}
 
+fn exit_with_error(context: &str, error: anyhow::Error) -> ! {
+    eprintln!("{}: {}", context, error);
+    std::process::exit(1);
+}
+
+async fn run(config_path: Option<PathBuf>) {
+    // Listen for SIGHUP before the startup review check, which would otherwise be killed by it.
+    let hangup = reload::Hangup::new();
+    let (review_check_sender, review_check) = tokio::sync::oneshot::channel();
+    tokio::task::spawn(reload::watch(config_path, hangup, review_check));
+
+    utility::health::init();
     tokio::task::spawn(async move {
-        schedule_background_review_check().await;
+        if let Some(review_check) = schedule_background_review_check().await {
+            let _ = review_check_sender.send(review_check);
+        }
     });
 
+    if let Some(bind_address) = get_config().http.bind_address.clone() {
+        tokio::task::spawn(async move { http::serve(bind_address.as_str()).await });
+    }
+
     let discord_client = discord::builder::build(config::get_config().discord_token.clone()).await;
     match discord_client {
         Ok(mut client) => {
@@ -51,19 +123,59 @@ async fn load_env() {
     }
 }
 
+fn load_config(config_path: Option<&Path>) {
+    match config::load(config_path) {
+        Ok(config) => config::init(config),
+        Err(errors) => {
+            eprintln!("Invalid configuration:");
+            for error in errors {
+                eprintln!("  - {}", error);
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
 async fn init_db() {
-    let mut conn = DbProvider::global()
-        .get_connection()
-        .expect("connect failed");
+    run_migrations().expect("migration failed");
+}
+
+/// Applies pending migrations and returns how many were applied.
+fn run_migrations() -> anyhow::Result<usize> {
+    let mut conn = DbProvider::global().get_connection()?;
+
+    match conn.run_pending_migrations(DIESEL_MIGRATIONS) {
+        Ok(applied) => Ok(applied.len()),
+        Err(e) => Err(anyhow::anyhow!("{}", e)),
+    }
+}
+
+/// Prints what one review check would deliver.
+async fn dry_run_check() {
+    let report = tokio::task::spawn_blocking(dry_run::check_for_new_reviews)
+        .await
+        .expect("failed to run dry-run review check");
+    println!("{}", report);
+}
 
-    conn.run_pending_migrations(DIESEL_MIGRATIONS)
-        .expect("migration failed");
+/// Runs one review check and waits until its deliveries are done.
+async fn check_now() {
+    tracing::info!("Running one-off review check...");
+    let deliveries = tokio::task::spawn(async move { worker::check_for_new_reviews() })
+        .await
+        .expect("failed to run review check");
+    for delivery in deliveries {
+        if let Err(e) = delivery.await {
+            tracing::error!("Delivery task failed: {}", e);
+        }
+    }
+    tracing::info!("Finished one-off review check.");
 }
 
-async fn schedule_background_review_check() {
+async fn schedule_background_review_check() -> Option<(JobScheduler, Uuid)> {
     let scheduler = JobScheduler::new().await.unwrap();
 
-    if get_config().fetch_reviews_on_startup {
+    if get_config().scheduler.fetch_reviews_on_startup {
         tokio::task::spawn(async move {
             tracing::info!("Running startup review check...");
             worker::check_for_new_reviews();
@@ -73,27 +185,37 @@ async fn schedule_background_review_check() {
             .expect("failed to run startup review check");
     }
 
-    let job = match Job::new(
-        get_config().new_review_fetch_interval.clone(),
-        |_uuid, _l| {
-            tracing::info!("Starting scheduled review fetch...");
-            worker::check_for_new_reviews();
-            tracing::info!("Finished scheduled review fetch.");
-        },
-    ) {
+    let schedule = get_config().scheduler.new_review_fetch_interval.clone();
+    let job = match worker::review_check_job(schedule.as_str()) {
         Ok(j) => j,
         Err(e) => {
             tracing::error!(
                 "Failed to create scheduled job with schedule '{}': '{}'",
-                get_config().new_review_fetch_interval.clone(),
+                schedule,
                 e
             );
-            return;
+            return None;
+        }
+    };
+
+    let review_job_id = scheduler.add(job).await.unwrap();
+
+    // Digest slots are minute-precise, so poll every minute for channels that are due.
+    let digest_job = match Job::new_async(DIGEST_CHECK_INTERVAL, |_uuid, _l| {
+        Box::pin(async move {
+            digest::send_due_digests().await;
+        })
+    }) {
+        Ok(j) => j,
+        Err(e) => {
+            tracing::error!("Failed to create digest job: '{}'", e);
+            return None;
         }
     };
 
-    scheduler.add(job).await.unwrap();
+    scheduler.add(digest_job).await.unwrap();
     scheduler.start().await.unwrap();
+    Some((scheduler, review_job_id))
 }
 
 async fn run_discord_client(client: &mut Client) {
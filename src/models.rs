@@ -1,5 +1,5 @@
-use crate::schema::{following, reviews, users};
-use chrono::NaiveDateTime;
+use crate::schema::{channel_settings, digest_queue, following, reviews, short_links, short_urls, sinks, subscriptions, users};
+use chrono::{NaiveDateTime, NaiveTime};
 use diesel::prelude::*;
 use serde_json::Value as JsonValue;
 
@@ -36,6 +36,8 @@ pub struct Review {
     pub found_at: NaiveDateTime,
     pub link_en: Option<String>,
     pub pictures: JsonValue,
+    /// Language code of the text the review was written in, when the crawler found it.
+    pub language: Option<String>,
 }
 
 #[derive(Insertable, Debug, Clone)]
@@ -49,6 +51,7 @@ pub struct NewReview {
     pub user_id: i32,
     pub link_en: String,
     pub pictures: JsonValue,
+    pub language: Option<String>,
 }
 
 #[derive(Queryable, Debug, Clone)]
@@ -71,6 +74,14 @@ pub struct Following {
     pub channel_id: String,
     pub original_text: bool,
     pub webhook_id: String,
+    pub mention_role_id: Option<String>,
+    pub mention_user_ids: JsonValue,
+    pub mention_here: bool,
+    pub thread_per_user: bool,
+    pub thread_id: Option<String>,
+    pub forum: bool,
+    /// Display language of the follow; the crawl language is used when unset.
+    pub language: Option<String>,
 }
 
 #[derive(Insertable, Debug)]
@@ -81,4 +92,127 @@ pub struct NewFollowing {
     pub channel_id: String,
     pub original_text: bool,
     pub webhook_id: String,
+    pub mention_role_id: Option<String>,
+    pub mention_user_ids: JsonValue,
+    pub mention_here: bool,
+    pub thread_per_user: bool,
+    pub forum: bool,
+    pub language: Option<String>,
+}
+
+// --- SUBSCRIPTION MODELS ---
+#[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone)]
+#[diesel(belongs_to(User, foreign_key = followed_user_id))]
+#[diesel(table_name = subscriptions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Subscription {
+    pub id: i32,
+    pub followed_user_id: i32,
+    pub discord_user_id: String,
+    pub original_text: bool,
+    /// Display language of the subscription; the crawl language is used when unset.
+    pub language: Option<String>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = subscriptions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewSubscription {
+    pub followed_user_id: i32,
+    pub discord_user_id: String,
+    pub original_text: bool,
+    pub language: Option<String>,
+}
+
+// --- SINK MODELS ---
+#[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone)]
+#[diesel(belongs_to(User, foreign_key = followed_user_id))]
+#[diesel(table_name = sinks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Sink {
+    pub id: i32,
+    pub followed_user_id: i32,
+    pub kind: String,
+    pub target: String,
+    pub secret: Option<String>,
+    pub config: JsonValue,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = sinks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewSink {
+    pub followed_user_id: i32,
+    pub kind: String,
+    pub target: String,
+    pub secret: Option<String>,
+    pub config: JsonValue,
+}
+
+// --- CHANNEL SETTINGS MODELS ---
+#[derive(Queryable, Selectable, Identifiable, Insertable, AsChangeset, Debug, Clone)]
+#[diesel(table_name = channel_settings)]
+#[diesel(primary_key(channel_id))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ChannelSettings {
+    pub channel_id: String,
+    pub delivery_mode: String,
+    pub digest_time: NaiveTime,
+    pub digest_weekday: i32,
+    pub timezone: String,
+    pub last_digest_at: Option<NaiveDateTime>,
+}
+
+// --- DIGEST QUEUE MODELS ---
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone)]
+#[diesel(table_name = digest_queue)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DigestEntry {
+    pub id: i32,
+    pub channel_id: String,
+    pub user_name: String,
+    pub gmaps_id: String,
+    pub place_name: String,
+    pub stars: i32,
+    pub link: Option<String>,
+    pub queued_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = digest_queue)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewDigestEntry {
+    pub channel_id: String,
+    pub user_name: String,
+    pub gmaps_id: String,
+    pub place_name: String,
+    pub stars: i32,
+    pub link: Option<String>,
+}
+
+// --- SHORT LINK MODELS ---
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone)]
+#[diesel(table_name = short_links)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ShortLink {
+    pub id: i32,
+    pub url: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = short_links)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewShortLink {
+    pub url: String,
+}
+
+// --- SHORT URL MODELS ---
+#[derive(Insertable, AsChangeset, Debug)]
+#[diesel(table_name = short_urls)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewShortUrl {
+    pub shortener: String,
+    pub original_url: String,
+    pub short_url: String,
 }
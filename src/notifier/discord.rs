@@ -0,0 +1,360 @@
+use crate::config::get_config;
+use crate::models::{Following, Subscription, User};
+use crate::notifier::{Notifier, ReviewEvent};
+use crate::provider::following;
+use crate::utility;
+use anyhow::Result;
+use async_trait::async_trait;
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// Held while a forum's tags are rewritten, so concurrent reviews don't overwrite each other's tags.
+static FORUM_TAG_LOCKS: LazyLock<Mutex<HashMap<serenity::ChannelId, Arc<tokio::sync::Mutex<()>>>>> =
+    LazyLock::new(Mutex::default);
+
+/// Discord rejects thread names longer than this.
+const THREAD_NAME_LIMIT: usize = 100;
+
+/// Posts reviews into a followed channel through the follow's webhook.
+pub struct DiscordWebhookNotifier {
+    following: Following,
+}
+
+impl DiscordWebhookNotifier {
+    pub fn new(following: Following) -> Self {
+        DiscordWebhookNotifier { following }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordWebhookNotifier {
+    fn target(&self) -> String {
+        format!("discord channel {}", self.following.channel_id)
+    }
+
+    fn kind(&self) -> &'static str {
+        "discord_channel"
+    }
+
+    async fn notify(&self, event: &ReviewEvent) -> Result<()> {
+        let following = &self.following;
+        let review = &event.review;
+        tracing::info!("Sending new review notification for user '{}' to channel '{}'", review.user.gmaps_id, following.channel_id);
+
+        let http = serenity::Http::new(get_config().discord_token.as_str());
+        let Some(webhook) = get_following_webhook(following, &http).await else {
+            return Err(anyhow::anyhow!("No usable webhook for channel {}", following.channel_id));
+        };
+
+        let current_user = match http.get_current_user().await {
+            Ok(user) => user,
+            Err(e) => {
+                return Err(anyhow::anyhow!("Failed to get current bot user: {}", e));
+            }
+        };
+
+        let language = following.language.as_deref();
+        let original = utility::language::shows_original(following.original_text, language, &review.review);
+        let localized = utility::language::localized_review(review, language);
+        let mentions = utility::mention::Mentions::from_following(following);
+        let mut webhook_message = serenity::ExecuteWebhook::new()
+            .allowed_mentions(mentions.allowed_mentions())
+            .username(current_user.name.clone())
+            .avatar_url(current_user.avatar_url().unwrap_or_default())
+            .embeds(utility::embed::get_review_embeds(&localized, original))
+            .components(utility::embed::get_review_components(&localized, original));
+        if let Some(content) = mentions.content() {
+            webhook_message = webhook_message.content(content);
+        }
+        if following.forum {
+            webhook_message = webhook_message.thread_name(utility::forum::forum_post_title(
+                review.review.place_name.as_str(),
+                review.review.stars,
+                get_config().star_text.as_str(),
+            ));
+            if let Some(tag_id) = ensure_star_tag(following.channel_id.as_str(), review.review.stars, &http).await {
+                webhook_message = webhook_message.applied_tags(vec![tag_id]);
+            }
+        } else if following.thread_per_user {
+            match ensure_user_thread(following, &review.user, &http).await {
+                Some(thread_id) => webhook_message = webhook_message.in_thread(thread_id),
+                None => tracing::warn!(
+                    "Falling back to channel root for user '{}' in channel '{}'",
+                    review.user.gmaps_id,
+                    following.channel_id
+                ),
+            }
+        }
+
+        match webhook.execute(&http, false, webhook_message).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(anyhow::anyhow!("Failed to send webhook message: {}", e)),
+        }
+    }
+}
+
+/// Sends reviews as direct messages to a subscribed Discord user.
+pub struct DiscordDmNotifier {
+    subscription: Subscription,
+}
+
+impl DiscordDmNotifier {
+    pub fn new(subscription: Subscription) -> Self {
+        DiscordDmNotifier { subscription }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordDmNotifier {
+    fn target(&self) -> String {
+        format!("discord user {}", self.subscription.discord_user_id)
+    }
+
+    fn kind(&self) -> &'static str {
+        "discord_dm"
+    }
+
+    async fn notify(&self, event: &ReviewEvent) -> Result<()> {
+        let subscription = &self.subscription;
+        let review = &event.review;
+        tracing::info!("Sending new review DM for user '{}' to discord user '{}'", review.user.gmaps_id, subscription.discord_user_id);
+
+        let Ok(discord_user_id) = subscription.discord_user_id.parse::<u64>() else {
+            return Err(anyhow::anyhow!(
+                "Invalid discord user ID in subscription {}: {}",
+                subscription.id,
+                subscription.discord_user_id
+            ));
+        };
+
+        let http = serenity::Http::new(get_config().discord_token.as_str());
+        let dm_channel = match serenity::UserId::new(discord_user_id).create_dm_channel(&http).await {
+            Ok(channel) => channel,
+            Err(e) => {
+                return Err(anyhow::anyhow!("Failed to open DM channel with discord user {}: {}", discord_user_id, e));
+            }
+        };
+
+        let language = subscription.language.as_deref();
+        let original = utility::language::shows_original(subscription.original_text, language, &review.review);
+        let localized = utility::language::localized_review(review, language);
+        let message = serenity::CreateMessage::new()
+            .embeds(utility::embed::get_review_embeds(&localized, original))
+            .components(utility::embed::get_review_components(&localized, original));
+        match dm_channel.send_message(&http, message).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(anyhow::anyhow!("Failed to send review DM to {}: {}", discord_user_id, e)),
+        }
+    }
+}
+
+/// Returns the follow's webhook, recreating it when it was deleted from the channel.
+pub(crate) async fn get_following_webhook(
+    following: &Following,
+    http: &serenity::Http,
+) -> Option<serenity::Webhook> {
+    let Some(webhook_id) = ensure_webhook_exists(
+        following.webhook_id.as_str(),
+        following.channel_id.as_str(),
+        http,
+    )
+        .await else {
+        tracing::error!(
+                "Failed to ensure webhook exists for channel_id: {}",
+                following.channel_id
+            );
+        return None;
+    };
+
+    if webhook_id != following.webhook_id {
+        match following::update_webhook(webhook_id.as_str(), following.channel_id.as_str()) {
+            Ok(()) => tracing::info!(
+                "Updated webhook ID for channel_id: {}",
+                following.channel_id
+            ),
+            Err(e) => tracing::error!(
+                "Failed to update webhook ID for channel_id: {}: {}",
+                following.channel_id,
+                e
+            ),
+        }
+    }
+
+    match serenity::Webhook::from_id(
+        http,
+        serenity::WebhookId::new(webhook_id.parse().unwrap()),
+    )
+        .await
+    {
+        Ok(wh) => Some(wh),
+        Err(e) => {
+            tracing::error!("Failed to fetch webhook by ID {}: {}", webhook_id, e);
+            None
+        }
+    }
+}
+
+/// Returns the follow's dedicated thread, re-opening it when archived and creating it when missing.
+async fn ensure_user_thread(
+    following: &Following,
+    user: &User,
+    http: &serenity::Http,
+) -> Option<serenity::ChannelId> {
+    if let Some(thread_id) = following.thread_id.as_deref().and_then(|id| id.parse::<u64>().ok()) {
+        let thread_id = serenity::ChannelId::new(thread_id);
+        match http.get_channel(thread_id).await {
+            Ok(serenity::Channel::Guild(thread)) => {
+                let is_archived = thread
+                    .thread_metadata
+                    .is_some_and(|metadata| metadata.archived);
+                if !is_archived {
+                    return Some(thread_id);
+                }
+
+                match thread_id
+                    .edit_thread(http, serenity::EditThread::new().archived(false))
+                    .await
+                {
+                    Ok(_) => {
+                        tracing::info!("Re-opened archived thread {} for user '{}'", thread_id, user.gmaps_id);
+                        return Some(thread_id);
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to re-open archived thread {}: {}", thread_id, e);
+                        return None;
+                    }
+                }
+            }
+            Ok(_) => tracing::warn!("Stored thread {} is not a guild thread, creating a new one", thread_id),
+            Err(e) => tracing::warn!("Failed to fetch thread {}, creating a new one: {}", thread_id, e),
+        }
+    }
+
+    let channel_id = serenity::ChannelId::new(following.channel_id.parse().ok()?);
+    let thread_name: String = user.name.chars().take(THREAD_NAME_LIMIT).collect();
+    let thread = match channel_id
+        .create_thread(
+            http,
+            serenity::CreateThread::new(thread_name).kind(serenity::ChannelType::PublicThread),
+        )
+        .await
+    {
+        Ok(t) => t,
+        Err(e) => {
+            tracing::error!("Failed to create thread for user '{}' in channel {}: {}", user.gmaps_id, channel_id, e);
+            return None;
+        }
+    };
+
+    if let Err(e) = following::update_thread(following.id, thread.id.to_string().as_str()) {
+        tracing::error!("Failed to store thread ID for following {}: {}", following.id, e);
+    }
+    Some(thread.id)
+}
+
+/// Returns the forum tag for a star rating, adding it to the forum when it doesn't exist yet.
+async fn ensure_star_tag(channel_id: &str, stars: i32, http: &serenity::Http) -> Option<serenity::ForumTagId> {
+    let channel_id = serenity::ChannelId::new(channel_id.parse().ok()?);
+    let tag_name = utility::forum::star_tag_name(stars);
+    let forum = get_forum(channel_id, http).await?;
+    if let Some(tag) = forum.available_tags.iter().find(|tag| tag.name == tag_name) {
+        return Some(tag.id);
+    }
+
+    // Another review may have added tags meanwhile, so they are read again once the forum is ours.
+    let lock = FORUM_TAG_LOCKS
+        .lock()
+        .ok()?
+        .entry(channel_id)
+        .or_default()
+        .clone();
+    let _guard = lock.lock().await;
+    let forum = get_forum(channel_id, http).await?;
+    if let Some(tag) = forum.available_tags.iter().find(|tag| tag.name == tag_name) {
+        return Some(tag.id);
+    }
+    if forum.available_tags.len() >= utility::forum::FORUM_TAG_LIMIT {
+        tracing::warn!("Forum {} has no room for tag '{}'", channel_id, tag_name);
+        return None;
+    }
+
+    // The channel builder can't carry tag IDs, so existing tags are re-sent as-is to keep them intact.
+    let mut tags: Vec<serde_json::Value> = forum
+        .available_tags
+        .iter()
+        .filter_map(|tag| serde_json::to_value(tag).ok())
+        .collect();
+    tags.push(serde_json::json!({ "name": tag_name }));
+
+    match http
+        .edit_channel(
+            channel_id,
+            &serde_json::json!({ "available_tags": tags }),
+            Some("Add star rating tag for review posts"),
+        )
+        .await
+    {
+        Ok(updated) => updated
+            .available_tags
+            .into_iter()
+            .find(|tag| tag.name == tag_name)
+            .map(|tag| tag.id),
+        Err(e) => {
+            tracing::error!("Failed to create tag '{}' in forum {}: {}", tag_name, channel_id, e);
+            None
+        }
+    }
+}
+
+async fn get_forum(channel_id: serenity::ChannelId, http: &serenity::Http) -> Option<serenity::GuildChannel> {
+    match http.get_channel(channel_id).await {
+        Ok(serenity::Channel::Guild(forum)) => Some(forum),
+        Ok(_) => {
+            tracing::error!("Channel {} is not a guild forum channel", channel_id);
+            None
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch forum channel {}: {}", channel_id, e);
+            None
+        }
+    }
+}
+
+async fn ensure_webhook_exists(
+    webhook: &str,
+    channel_id: &str,
+    http: &serenity::Http,
+) -> Option<String> {
+    let webhook_id = serenity::WebhookId::new(webhook.parse().unwrap());
+
+    match http.get_webhook(webhook_id).await {
+        Ok(_) => Some(webhook.to_string()),
+        Err(e) => {
+            if let serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(resp)) = &e
+                && resp.status_code == 403
+            {
+                tracing::error!(
+                    "Missing permissions to access or create webhook in channel {}",
+                    channel_id
+                );
+                return None;
+            }
+
+            match http
+                .create_webhook(
+                    serenity::ChannelId::new(channel_id.parse().unwrap()),
+                    &(),
+                    None,
+                )
+                .await
+            {
+                Ok(webhook) => Some(webhook.id.to_string()),
+                Err(e) => {
+                    tracing::error!("Failed to create webhook: {}", e);
+                    None
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::config::{get_config, SmtpConfig, SmtpTls};
+use crate::crawler::pages::user::GMAPS_USER_URL;
+use crate::models::{DigestEntry, Sink};
+use crate::notifier::{Notifier, ReviewEvent};
+use crate::utility;
+use anyhow::Result;
+use async_trait::async_trait;
+use lettre::message::MultiPart;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use minijinja::{context, Environment};
+use std::borrow::Cow;
+use std::sync::{Arc, RwLock};
+
+pub const SINK_KIND: &str = "email";
+
+static REVIEW_HTML_TEMPLATE: &str = include_str!("../../templates/email/review.html");
+static REVIEW_TEXT_TEMPLATE: &str = include_str!("../../templates/email/review.txt");
+static DIGEST_HTML_TEMPLATE: &str = include_str!("../../templates/email/digest.html");
+static DIGEST_TEXT_TEMPLATE: &str = include_str!("../../templates/email/digest.txt");
+
+/// Shared by all email sinks so their connections are pooled.
+static TRANSPORT: RwLock<Option<Arc<AsyncSmtpTransport<Tokio1Executor>>>> = RwLock::new(None);
+
+/// A rendered email, ready to be addressed and sent.
+#[derive(Debug)]
+pub struct EmailContent {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Sends reviews as multipart HTML and plain-text email over SMTP.
+pub struct EmailNotifier {
+    smtp: SmtpConfig,
+    to: String,
+    original: bool,
+}
+
+impl EmailNotifier {
+    pub fn new(smtp: SmtpConfig, to: String, original: bool) -> Self {
+        EmailNotifier { smtp, to, original }
+    }
+
+    pub fn from_sink(sink: &Sink) -> Result<Self> {
+        let smtp = get_config().sinks.smtp.clone();
+        if smtp.host.is_none() {
+            return Err(anyhow::anyhow!("Email sink {} can't be used without SMTP_HOST", sink.id));
+        }
+
+        Ok(EmailNotifier::new(smtp, sink.target.clone(), super::sink_original_text(sink)))
+    }
+
+    async fn send(&self, content: EmailContent) -> Result<()> {
+        let message = Message::builder()
+            .from(self.smtp.from.parse()?)
+            .to(self.to.parse()?)
+            .subject(content.subject)
+            .multipart(MultiPart::alternative_plain_html(content.text, content.html))?;
+
+        match current_transport(&self.smtp)?.send(message).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(anyhow::anyhow!("Failed to send email to {}: {}", self.to, e)),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn target(&self) -> String {
+        format!("email {}", self.to)
+    }
+
+    fn kind(&self) -> &'static str {
+        SINK_KIND
+    }
+
+    async fn notify(&self, event: &ReviewEvent) -> Result<()> {
+        let content = render_review_email(
+            event,
+            self.original,
+            get_config().star_text.as_str(),
+            self.smtp.template_dir.as_deref(),
+        )?;
+        self.send(content).await
+    }
+
+    async fn notify_digest(&self, title: &str, entries: &[DigestEntry]) -> Result<()> {
+        let content = render_digest_email(
+            title,
+            entries,
+            get_config().star_text.as_str(),
+            self.smtp.template_dir.as_deref(),
+        )?;
+        self.send(content).await
+    }
+}
+
+fn current_transport(smtp: &SmtpConfig) -> Result<Arc<AsyncSmtpTransport<Tokio1Executor>>> {
+    if let Some(transport) = TRANSPORT.read().ok().and_then(|transport| transport.clone()) {
+        return Ok(transport);
+    }
+
+    let transport = Arc::new(smtp_transport(smtp)?);
+    if let Ok(mut current) = TRANSPORT.write() {
+        *current = Some(transport.clone());
+    }
+    Ok(transport)
+}
+
+/// Drops the current transport so the next email connects with the reloaded SMTP settings.
+pub fn reset_transport() {
+    if let Ok(mut current) = TRANSPORT.write() {
+        *current = None;
+    }
+}
+
+fn smtp_transport(smtp: &SmtpConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+    let Some(host) = smtp.host.as_deref() else {
+        return Err(anyhow::anyhow!("SMTP is not configured"));
+    };
+
+    let mut builder = match smtp.tls {
+        SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+        SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+        SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+    }
+        .port(smtp.port);
+    if let (Some(username), Some(password)) = (&smtp.username, &smtp.password) {
+        builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+    Ok(builder.build())
+}
+
+pub fn render_review_email(
+    event: &ReviewEvent,
+    original: bool,
+    star_text: &str,
+    template_dir: Option<&str>,
+) -> Result<EmailContent> {
+    let review = &event.review.review;
+    let user = &event.review.user;
+    let stars = star_text.repeat(review.stars.try_into().unwrap_or_default());
+    let ctx = context! {
+        place => review.place_name,
+        place_url => review.link_en,
+        stars => stars,
+        body => utility::embed::select_review_body(&event.review, original),
+        pictures => utility::embed::collect_valid_pictures(&review.pictures),
+        user_name => user.name,
+        user_url => GMAPS_USER_URL.replace("{}", user.gmaps_id.as_str()),
+    };
+
+    Ok(EmailContent {
+        subject: format!("New review by {}: {} {}", user.name, review.place_name, stars),
+        text: render_template("review.txt", REVIEW_TEXT_TEMPLATE, template_dir, &ctx)?,
+        html: render_template("review.html", REVIEW_HTML_TEMPLATE, template_dir, &ctx)?,
+    })
+}
+
+pub fn render_digest_email(
+    title: &str,
+    entries: &[DigestEntry],
+    star_text: &str,
+    template_dir: Option<&str>,
+) -> Result<EmailContent> {
+    let entries: Vec<minijinja::Value> = entries
+        .iter()
+        .map(|entry| {
+            context! {
+                user_name => entry.user_name,
+                place => entry.place_name,
+                link => entry.link,
+                stars => star_text.repeat(entry.stars.max(0).try_into().unwrap_or_default()),
+            }
+        })
+        .collect();
+    let ctx = context! { title => title, entries => entries };
+
+    Ok(EmailContent {
+        subject: title.to_string(),
+        text: render_template("digest.txt", DIGEST_TEXT_TEMPLATE, template_dir, &ctx)?,
+        html: render_template("digest.html", DIGEST_HTML_TEMPLATE, template_dir, &ctx)?,
+    })
+}
+
+/// Renders a template from the override directory when it has one, else the built-in version.
+///
+/// The name's extension decides whether values are HTML-escaped.
+fn render_template(
+    name: &str,
+    builtin: &'static str,
+    template_dir: Option<&str>,
+    ctx: &minijinja::Value,
+) -> Result<String> {
+    let source: Cow<'static, str> = match template_dir
+        .map(|dir| std::path::Path::new(dir).join(name))
+        .filter(|path| path.is_file())
+    {
+        Some(path) => Cow::Owned(std::fs::read_to_string(path)?),
+        None => Cow::Borrowed(builtin),
+    };
+
+    let env = Environment::new();
+    let template = env.template_from_named_str(name, source.as_ref())?;
+    Ok(template.render(ctx)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_digest_email, render_review_email, EmailContent, EmailNotifier};
+    use crate::config::{SmtpConfig, SmtpTls};
+    use crate::models::DigestEntry;
+    use crate::notifier::tests::sample_event;
+    use chrono::Utc;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Accepts a single SMTP session and returns the raw message it received.
+    async fn capture_one_email(listener: TcpListener) -> String {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (read, mut write) = stream.into_split();
+        let mut lines = BufReader::new(read).lines();
+        write.write_all(b"220 capture ESMTP\r\n").await.unwrap();
+
+        let mut data = String::new();
+        let mut in_data = false;
+        while let Some(line) = lines.next_line().await.unwrap() {
+            if in_data {
+                if line == "." {
+                    write.write_all(b"250 OK\r\n").await.unwrap();
+                    break;
+                }
+                data.push_str(line.as_str());
+                data.push('\n');
+                continue;
+            }
+
+            let command = line.to_uppercase();
+            let reply: &[u8] = if command.starts_with("EHLO") {
+                b"250-capture\r\n250 8BITMIME\r\n"
+            } else if command.starts_with("DATA") {
+                in_data = true;
+                b"354 End data with <CR><LF>.<CR><LF>\r\n"
+            } else {
+                b"250 OK\r\n"
+            };
+            write.write_all(reply).await.unwrap();
+        }
+        data
+    }
+
+    #[test]
+    fn render_review_email_escapes_html() {
+        let mut event = sample_event();
+        event.review.review.place_name = "<b>Cafe</b>".to_string();
+        let content = render_review_email(&event, true, "*", None).unwrap();
+
+        assert_eq!(content.subject, "New review by Alice: <b>Cafe</b> ****");
+        assert!(content.html.contains("&lt;b&gt;Cafe&lt;&#x2f;b&gt;"));
+        assert!(content.html.contains("<img src=\"https:&#x2f;&#x2f;img&#x2f;1\""));
+        assert!(content.text.contains("<b>Cafe</b>\n****\n"));
+        assert!(content.text.contains("Geweldige koffie"));
+    }
+
+    #[test]
+    fn render_digest_email_lists_entries() {
+        let entries = vec![DigestEntry {
+            id: 1,
+            channel_id: "sink:1".to_string(),
+            user_name: "Alice".to_string(),
+            gmaps_id: "gmaps-42".to_string(),
+            place_name: "Cafe".to_string(),
+            stars: 3,
+            link: None,
+            queued_at: Utc::now().naive_utc(),
+        }];
+        let content = render_digest_email("Daily review digest", &entries, "*", None).unwrap();
+
+        assert_eq!(content.subject, "Daily review digest");
+        assert!(content.text.contains("- Alice reviewed Cafe ***"));
+        assert!(content.html.contains("<strong>Alice</strong> reviewed Cafe ***"));
+    }
+
+    #[test]
+    fn render_review_email_prefers_template_dir() {
+        let dir = std::env::temp_dir().join(format!("gmaps-email-templates-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("review.txt"), "Custom: {{ place }}").unwrap();
+
+        let content = render_review_email(&sample_event(), true, "*", dir.to_str()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(content.text, "Custom: Cafe");
+        assert!(content.html.contains("<h2>"));
+    }
+
+    #[tokio::test]
+    async fn send_delivers_multipart_message() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let capture = tokio::spawn(capture_one_email(listener));
+
+        let smtp = SmtpConfig {
+            host: Some("127.0.0.1".to_string()),
+            port,
+            tls: SmtpTls::None,
+            username: None,
+            password: None,
+            from: "Reviews <reviews@example.com>".to_string(),
+            template_dir: None,
+        };
+        let notifier = EmailNotifier::new(smtp, "reader@example.com".to_string(), true);
+        notifier
+            .send(EmailContent {
+                subject: "New review".to_string(),
+                text: "Plain body".to_string(),
+                html: "<p>HTML body</p>".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let data = capture.await.unwrap();
+        assert!(data.contains("Subject: New review"));
+        assert!(data.contains("To: reader@example.com"));
+        assert!(data.contains("multipart/alternative"));
+        assert!(data.contains("Plain body"));
+        assert!(data.contains("<p>HTML body</p>"));
+    }
+}
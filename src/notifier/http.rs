@@ -0,0 +1,138 @@
+use crate::models::Sink;
+use crate::notifier::{Notifier, ReviewEvent, ReviewPayload};
+use anyhow::Result;
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use sha2::Sha256;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+pub const SINK_KIND: &str = "webhook";
+pub const SIGNATURE_HEADER: &str = "X-Signature-256";
+pub const EVENT_HEADER: &str = "X-Review-Event";
+
+static SINK_CLIENT: OnceLock<Client> = OnceLock::new();
+
+fn sink_client() -> &'static Client {
+    SINK_CLIENT.get_or_init(|| {
+        Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("failed to build sink HTTP client")
+    })
+}
+
+/// POSTs the JSON review payload to an arbitrary URL, signed when the sink has a secret.
+pub struct HttpNotifier {
+    url: String,
+    secret: Option<String>,
+}
+
+impl HttpNotifier {
+    pub fn new(url: String, secret: Option<String>) -> Self {
+        HttpNotifier { url, secret }
+    }
+
+    pub fn from_sink(sink: &Sink) -> Self {
+        HttpNotifier::new(sink.target.clone(), sink.secret.clone())
+    }
+}
+
+#[async_trait]
+impl Notifier for HttpNotifier {
+    fn target(&self) -> String {
+        self.url.clone()
+    }
+
+    fn kind(&self) -> &'static str {
+        SINK_KIND
+    }
+
+    async fn notify(&self, event: &ReviewEvent) -> Result<()> {
+        let body = serde_json::to_vec(&ReviewPayload::from_event(event))?;
+        let event_name = serde_json::to_value(event.event_type)?;
+
+        let mut request = sink_client()
+            .post(self.url.as_str())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event_name.as_str().unwrap_or_default());
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+        }
+
+        let resp = match request.body(body).send().await {
+            Ok(resp) => resp,
+            Err(err) => {
+                return Err(anyhow::anyhow!("Failed to send request to sink: {err}"));
+            }
+        };
+
+        if !resp.status().is_success() {
+            return Err(anyhow::anyhow!("Sink responded with: {}", resp.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Hex HMAC-SHA256 of the request body, in the `sha256=<hex>` form GitHub-style receivers expect.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sign, HttpNotifier, EVENT_HEADER, SIGNATURE_HEADER};
+    use crate::notifier::tests::sample_event;
+    use crate::notifier::Notifier;
+    use wiremock::matchers::{header, header_exists, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn sign_matches_known_hmac() {
+        assert_eq!(
+            sign("It's a Secret to Everybody", b"Hello, World!"),
+            "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+        );
+    }
+
+    #[tokio::test]
+    async fn notify_posts_signed_payload() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header(EVENT_HEADER, "review.new"))
+            .and(header_exists(SIGNATURE_HEADER))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let notifier = HttpNotifier::new(format!("{}/hook", server.uri()), Some("secret".to_string()));
+        notifier.notify(&sample_event()).await.unwrap();
+
+        let request = &server.received_requests().await.unwrap()[0];
+        let signature = request.headers.get(SIGNATURE_HEADER).unwrap().to_str().unwrap();
+        assert_eq!(signature, sign("secret", &request.body));
+
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(body["review"]["id"], 7);
+        assert_eq!(body["place"]["name"], "Cafe");
+    }
+
+    #[tokio::test]
+    async fn notify_fails_on_error_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let notifier = HttpNotifier::new(server.uri(), None);
+        assert!(notifier.notify(&sample_event()).await.is_err());
+        let request = &server.received_requests().await.unwrap()[0];
+        assert!(request.headers.get(SIGNATURE_HEADER).is_none());
+    }
+}
@@ -0,0 +1,289 @@
+use crate::config::get_config;
+use crate::crawler::pages::user::GMAPS_USER_URL;
+use crate::models::Sink;
+use crate::notifier::{Notifier, ReviewEvent};
+use crate::utility;
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+pub const SINK_KIND: &str = "matrix";
+/// Matches the Discord gallery so large reviews don't flood the room.
+const IMAGE_LIMIT: usize = 4;
+
+static MATRIX_CLIENT: OnceLock<Client> = OnceLock::new();
+/// Keeps transaction IDs unique when several events are sent within the same millisecond.
+static TXN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Deserialize)]
+struct UploadResponse {
+    content_uri: String,
+}
+
+fn matrix_client() -> &'static Client {
+    MATRIX_CLIENT.get_or_init(|| {
+        Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("failed to build Matrix HTTP client")
+    })
+}
+
+/// Posts reviews into a Matrix room through the client-server API.
+pub struct MatrixNotifier {
+    homeserver: String,
+    room_id: String,
+    access_token: String,
+    original: bool,
+}
+
+impl MatrixNotifier {
+    pub fn new(homeserver: String, room_id: String, access_token: String, original: bool) -> Self {
+        MatrixNotifier {
+            homeserver: homeserver.trim_end_matches('/').to_string(),
+            room_id,
+            access_token,
+            original,
+        }
+    }
+
+    pub fn from_sink(sink: &Sink) -> Result<Self> {
+        let Some(homeserver) = sink.config.get("homeserver").and_then(Value::as_str) else {
+            return Err(anyhow::anyhow!("Matrix sink {} has no homeserver configured", sink.id));
+        };
+        let Some(access_token) = sink.secret.clone() else {
+            return Err(anyhow::anyhow!("Matrix sink {} has no access token", sink.id));
+        };
+
+        Ok(MatrixNotifier::new(
+            homeserver.to_string(),
+            sink.target.clone(),
+            access_token,
+            super::sink_original_text(sink),
+        ))
+    }
+
+    async fn deliver(&self, event: &ReviewEvent, star_text: &str) -> Result<()> {
+        self.send_event(build_matrix_message(event, self.original, star_text)).await?;
+
+        let pictures = utility::embed::collect_valid_pictures(&event.review.review.pictures);
+        for picture in pictures.into_iter().take(IMAGE_LIMIT) {
+            // A missing picture shouldn't hold back the rest of the review.
+            match self.upload_picture(picture).await {
+                Ok(content_uri) => {
+                    self.send_event(json!({
+                        "msgtype": "m.image",
+                        "body": event.review.review.place_name,
+                        "url": content_uri
+                    }))
+                        .await?;
+                }
+                Err(e) => tracing::warn!("Failed to upload picture '{}' to Matrix: {}", picture, e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-hosts a picture in the homeserver's media repository and returns its `mxc://` URI.
+    async fn upload_picture(&self, picture_url: &str) -> Result<String> {
+        let picture = matrix_client().get(picture_url).send().await?.error_for_status()?;
+        let content_type = picture
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("image/jpeg")
+            .to_string();
+        let bytes = picture.bytes().await?;
+
+        let resp = matrix_client()
+            .post(format!("{}/_matrix/media/v3/upload", self.homeserver))
+            .bearer_auth(self.access_token.as_str())
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(bytes)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(anyhow::anyhow!("Media upload failed: {}", resp.status()));
+        }
+
+        let upload: UploadResponse = resp.json().await?;
+        Ok(upload.content_uri)
+    }
+
+    async fn send_event(&self, content: Value) -> Result<()> {
+        let txn_id = format!(
+            "gmaps-{}-{}",
+            chrono::Utc::now().timestamp_millis(),
+            TXN_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let url = format!(
+            "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
+            self.homeserver,
+            urlencoding::encode(self.room_id.as_str()),
+            txn_id
+        );
+
+        let resp = match matrix_client()
+            .put(url)
+            .bearer_auth(self.access_token.as_str())
+            .json(&content)
+            .send()
+            .await
+        {
+            Ok(resp) => resp,
+            Err(err) => {
+                return Err(anyhow::anyhow!("Failed to send request to Matrix homeserver: {err}"));
+            }
+        };
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Matrix homeserver responded with {}: {}", status, body));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for MatrixNotifier {
+    fn target(&self) -> String {
+        format!("matrix room {}", self.room_id)
+    }
+
+    fn kind(&self) -> &'static str {
+        SINK_KIND
+    }
+
+    async fn notify(&self, event: &ReviewEvent) -> Result<()> {
+        self.deliver(event, get_config().star_text.as_str()).await
+    }
+}
+
+/// Renders a review as an HTML-formatted `m.text` event with a plain-text fallback body.
+pub fn build_matrix_message(event: &ReviewEvent, original: bool, star_text: &str) -> Value {
+    let review = &event.review.review;
+    let user = &event.review.user;
+    let user_url = GMAPS_USER_URL.replace("{}", user.gmaps_id.as_str());
+    let stars = star_text.repeat(review.stars.try_into().unwrap_or_default());
+    let body = utility::embed::select_review_body(&event.review, original);
+
+    let title_html = match &review.link_en {
+        Some(link) => format!("<a href=\"{}\">{}</a>", escape_html(link), escape_html(review.place_name.as_str())),
+        None => escape_html(review.place_name.as_str()),
+    };
+    let formatted_body = format!(
+        "<h4>{}</h4><p>{}</p><p>{}</p><p><em>Review by <a href=\"{}\">{}</a></em></p>",
+        title_html,
+        escape_html(stars.as_str()),
+        escape_html(body).replace('\n', "<br>"),
+        escape_html(user_url.as_str()),
+        escape_html(user.name.as_str())
+    );
+    let plain_body = format!(
+        "{}\n{}\n\n{}\n\nReview by {} ({})",
+        review.place_name, stars, body, user.name, user_url
+    );
+
+    json!({
+        "msgtype": "m.text",
+        "body": plain_body,
+        "format": "org.matrix.custom.html",
+        "formatted_body": formatted_body
+    })
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_matrix_message, escape_html, MatrixNotifier};
+    use crate::notifier::tests::sample_event;
+    use wiremock::matchers::{body_partial_json, header, method, path, path_regex};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn build_matrix_message_renders_html() {
+        let message = build_matrix_message(&sample_event(), false, "⭐");
+
+        assert_eq!(message["msgtype"], "m.text");
+        assert_eq!(message["format"], "org.matrix.custom.html");
+        assert_eq!(
+            message["formatted_body"],
+            "<h4><a href=\"https://example.com/review\">Cafe</a></h4><p>⭐⭐⭐⭐</p><p>Great coffee</p>\
+             <p><em>Review by <a href=\"https://www.google.com/maps/contrib/gmaps-42/reviews?hl=en\">Alice</a></em></p>"
+        );
+        assert!(message["body"].as_str().unwrap().contains("Great coffee"));
+    }
+
+    #[test]
+    fn escape_html_escapes_markup() {
+        assert_eq!(escape_html("<script>\"x\" & 'y'"), "&lt;script&gt;&quot;x&quot; &amp; &#39;y&#39;");
+    }
+
+    #[tokio::test]
+    async fn deliver_uploads_pictures_and_sends_events() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/img/[12]$"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(vec![0xFF, 0xD8, 0xFF], "image/jpeg"))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/_matrix/media/v3/upload"))
+            .and(header("authorization", "Bearer token"))
+            .and(header("content-type", "image/jpeg"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "content_uri": "mxc://hs/abc" })))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path_regex("^/_matrix/client/v3/rooms/%21room%3Ahs/send/m.room.message/.+$"))
+            .and(body_partial_json(serde_json::json!({ "msgtype": "m.text" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "event_id": "$1" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(body_partial_json(serde_json::json!({ "msgtype": "m.image", "url": "mxc://hs/abc" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "event_id": "$2" })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let mut event = sample_event();
+        event.review.review.pictures = serde_json::json!([
+            format!("{}/img/1", server.uri()),
+            format!("{}/img/2", server.uri())
+        ]);
+        let notifier = MatrixNotifier::new(format!("{}/", server.uri()), "!room:hs".to_string(), "token".to_string(), true);
+        notifier.deliver(&event, "⭐").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn deliver_fails_when_homeserver_rejects_event() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("M_FORBIDDEN"))
+            .mount(&server)
+            .await;
+
+        let mut event = sample_event();
+        event.review.review.pictures = serde_json::json!([]);
+        let notifier = MatrixNotifier::new(server.uri(), "!room:hs".to_string(), "token".to_string(), true);
+        let err = notifier.deliver(&event, "⭐").await.unwrap_err();
+        assert!(err.to_string().contains("M_FORBIDDEN"));
+    }
+}
@@ -0,0 +1,218 @@
+use crate::crawler::pages::user::GMAPS_USER_URL;
+use crate::models::{DigestEntry, ReviewWithUser, Sink};
+use crate::utility;
+use crate::utility::metrics;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use tracing::Instrument;
+
+pub mod discord;
+pub mod email;
+pub mod http;
+pub mod matrix;
+pub mod slack;
+pub mod telegram;
+
+/// Why a review is being delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum EventType {
+    /// A followed user posted a review that wasn't seen before.
+    #[serde(rename = "review.new")]
+    NewReview,
+    /// The latest known review, sent once when a target starts following a user.
+    #[serde(rename = "review.latest")]
+    LatestReview,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReviewEvent {
+    pub event_type: EventType,
+    pub review: ReviewWithUser,
+}
+
+/// A destination new reviews can be delivered to.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Describes the destination in log messages.
+    fn target(&self) -> String;
+
+    /// Labels deliveries to this kind of destination in the metrics.
+    fn kind(&self) -> &'static str;
+
+    async fn notify(&self, event: &ReviewEvent) -> Result<()>;
+
+    /// Delivers a batch of queued reviews; only targets that support digests override this.
+    async fn notify_digest(&self, _title: &str, _entries: &[DigestEntry]) -> Result<()> {
+        Err(anyhow::anyhow!("{} does not support digests", self.target()))
+    }
+}
+
+/// Builds the notifier for a sink registered on a followed user.
+pub fn from_sink(sink: &Sink) -> Result<Box<dyn Notifier>> {
+    match sink.kind.as_str() {
+        http::SINK_KIND => Ok(Box::new(http::HttpNotifier::from_sink(sink))),
+        slack::SINK_KIND => Ok(Box::new(slack::SlackNotifier::from_sink(sink))),
+        matrix::SINK_KIND => Ok(Box::new(matrix::MatrixNotifier::from_sink(sink)?)),
+        telegram::SINK_KIND => Ok(Box::new(telegram::TelegramNotifier::from_sink(sink)?)),
+        email::SINK_KIND => Ok(Box::new(email::EmailNotifier::from_sink(sink)?)),
+        kind => Err(anyhow::anyhow!("Unknown sink kind '{kind}' for sink {}", sink.id)),
+    }
+}
+
+/// Whether a sink renders the original review text rather than the translation; defaults to the original like `/follow`.
+fn sink_original_text(sink: &Sink) -> bool {
+    sink.config
+        .get("original")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(true)
+}
+
+/// Delivers an event and logs the outcome.
+pub async fn dispatch(notifier: Box<dyn Notifier>, event: ReviewEvent) {
+    let span = tracing::info_span!(
+        "notification",
+        kind = notifier.kind(),
+        target = %notifier.target(),
+        review_id = event.review.review.id,
+        gmaps_id = %event.review.user.gmaps_id
+    );
+
+    async {
+        let result = notifier.notify(&event).await;
+        metrics::record_notification(notifier.kind(), result.is_ok());
+        match result {
+            Ok(()) => tracing::debug!("Delivered review"),
+            Err(e) => tracing::error!(error = %e, "Failed to deliver review"),
+        }
+    }
+        .instrument(span)
+        .await
+}
+
+/// The JSON document sent to generic webhook sinks.
+#[derive(Debug, Serialize)]
+pub struct ReviewPayload {
+    pub event: EventType,
+    pub user: UserPayload,
+    pub review: ReviewBodyPayload,
+    pub place: PlacePayload,
+    pub pictures: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserPayload {
+    pub gmaps_id: String,
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewBodyPayload {
+    pub id: i32,
+    pub text: String,
+    pub original_text: Option<String>,
+    /// Language code of the text the review was written in, when known.
+    pub language: Option<String>,
+    pub stars: i32,
+    pub url: Option<String>,
+    pub found_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlacePayload {
+    pub name: String,
+}
+
+impl ReviewPayload {
+    pub fn from_event(event: &ReviewEvent) -> Self {
+        let ReviewWithUser { user, review } = &event.review;
+        ReviewPayload {
+            event: event.event_type,
+            user: UserPayload {
+                gmaps_id: user.gmaps_id.clone(),
+                name: user.name.clone(),
+                url: GMAPS_USER_URL.replace("{}", user.gmaps_id.as_str()),
+            },
+            review: ReviewBodyPayload {
+                id: review.id,
+                text: review.text.clone(),
+                original_text: review.original_text.clone(),
+                language: review.language.clone(),
+                stars: review.stars,
+                url: review.link_en.clone(),
+                found_at: review.found_at.and_utc().to_rfc3339(),
+            },
+            place: PlacePayload {
+                name: review.place_name.clone(),
+            },
+            pictures: utility::embed::collect_valid_pictures(&review.pictures)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{EventType, ReviewEvent, ReviewPayload};
+    use crate::models::{Review, ReviewWithUser, User};
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    pub(crate) fn sample_event() -> ReviewEvent {
+        ReviewEvent {
+            event_type: EventType::NewReview,
+            review: ReviewWithUser {
+                user: User {
+                    id: 42,
+                    gmaps_id: "gmaps-42".to_string(),
+                    name: "Alice".to_string(),
+                },
+                review: Review {
+                    id: 7,
+                    place_name: "Cafe".to_string(),
+                    text: "Great coffee".to_string(),
+                    original_text: Some("Geweldige koffie".to_string()),
+                    stars: 4,
+                    user_id: 42,
+                    found_at: NaiveDate::from_ymd_opt(2026, 1, 15)
+                        .unwrap()
+                        .and_hms_opt(9, 30, 0)
+                        .unwrap(),
+                    link_en: Some("https://example.com/review".to_string()),
+                    pictures: json!(["https://img/1", "", "https://img/2"]),
+                    language: Some("nl".to_string()),
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn review_payload_matches_documented_format() {
+        let payload = serde_json::to_value(ReviewPayload::from_event(&sample_event())).unwrap();
+        assert_eq!(
+            payload,
+            json!({
+                "event": "review.new",
+                "user": {
+                    "gmaps_id": "gmaps-42",
+                    "name": "Alice",
+                    "url": "https://www.google.com/maps/contrib/gmaps-42/reviews?hl=en"
+                },
+                "review": {
+                    "id": 7,
+                    "text": "Great coffee",
+                    "original_text": "Geweldige koffie",
+                    "language": "nl",
+                    "stars": 4,
+                    "url": "https://example.com/review",
+                    "found_at": "2026-01-15T09:30:00+00:00"
+                },
+                "place": { "name": "Cafe" },
+                "pictures": ["https://img/1", "https://img/2"]
+            })
+        );
+    }
+}
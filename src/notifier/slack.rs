@@ -0,0 +1,223 @@
+use crate::config::get_config;
+use crate::crawler::pages::user::GMAPS_USER_URL;
+use crate::models::Sink;
+use crate::notifier::{Notifier, ReviewEvent};
+use crate::utility;
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+pub const SINK_KIND: &str = "slack";
+/// Slack truncates header blocks after this many characters.
+const HEADER_TEXT_LIMIT: usize = 150;
+/// Slack rejects section blocks whose text exceeds this many characters.
+const SECTION_TEXT_LIMIT: usize = 3000;
+
+static SLACK_CLIENT: OnceLock<Client> = OnceLock::new();
+
+fn slack_client() -> &'static Client {
+    SLACK_CLIENT.get_or_init(|| {
+        Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("failed to build Slack HTTP client")
+    })
+}
+
+/// Posts reviews as Block Kit messages to a Slack incoming webhook.
+pub struct SlackNotifier {
+    webhook_url: String,
+    original: bool,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: String, original: bool) -> Self {
+        SlackNotifier { webhook_url, original }
+    }
+
+    pub fn from_sink(sink: &Sink) -> Self {
+        SlackNotifier::new(sink.target.clone(), super::sink_original_text(sink))
+    }
+
+    async fn send(&self, message: &Value) -> Result<()> {
+        let resp = match slack_client().post(self.webhook_url.as_str()).json(message).send().await {
+            Ok(resp) => resp,
+            Err(err) => {
+                return Err(anyhow::anyhow!("Failed to send request to Slack: {err}"));
+            }
+        };
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Slack responded with {}: {}", status, body));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn target(&self) -> String {
+        "slack incoming webhook".to_string()
+    }
+
+    fn kind(&self) -> &'static str {
+        SINK_KIND
+    }
+
+    async fn notify(&self, event: &ReviewEvent) -> Result<()> {
+        let message = build_slack_message(event, self.original, get_config().star_text.as_str());
+        self.send(&message).await
+    }
+}
+
+/// Renders a review as a Block Kit message with a plain-text fallback for notifications.
+pub fn build_slack_message(event: &ReviewEvent, original: bool, star_text: &str) -> Value {
+    let review = &event.review.review;
+    let user = &event.review.user;
+    let user_url = GMAPS_USER_URL.replace("{}", user.gmaps_id.as_str());
+    let stars = star_text.repeat(review.stars.try_into().unwrap_or_default());
+    let body = utility::embed::select_review_body(&event.review, original);
+
+    let mut blocks = vec![
+        json!({
+            "type": "header",
+            "text": {
+                "type": "plain_text",
+                "text": truncate(review.place_name.as_str(), HEADER_TEXT_LIMIT),
+                "emoji": true
+            }
+        }),
+        json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": escape_mrkdwn(stars.as_str()) }
+        }),
+    ];
+    if !body.trim().is_empty() {
+        blocks.push(json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": truncate(escape_mrkdwn(body).as_str(), SECTION_TEXT_LIMIT)
+            }
+        }));
+    }
+    if let Some(picture) = utility::embed::collect_valid_pictures(&review.pictures).first() {
+        blocks.push(json!({
+            "type": "image",
+            "image_url": picture,
+            "alt_text": review.place_name
+        }));
+    }
+
+    let mut context = format!("Review by <{}|{}>", user_url, escape_mrkdwn(user.name.as_str()));
+    if let Some(link) = &review.link_en {
+        context.push_str(format!(" · <{}|View on Google Maps>", link).as_str());
+    }
+    blocks.push(json!({
+        "type": "context",
+        "elements": [{ "type": "mrkdwn", "text": context }]
+    }));
+
+    json!({
+        "text": format!("New review by {} for {}: {}", user.name, review.place_name, stars),
+        "blocks": blocks
+    })
+}
+
+/// Escapes the characters Slack treats as control sequences in mrkdwn text.
+fn escape_mrkdwn(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(limit - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_slack_message, escape_mrkdwn, truncate, SlackNotifier};
+    use crate::notifier::tests::sample_event;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn build_slack_message_renders_blocks() {
+        let message = build_slack_message(&sample_event(), true, "⭐");
+        let blocks = message["blocks"].as_array().unwrap();
+
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(blocks[0]["text"]["text"], "Cafe");
+        assert_eq!(blocks[1]["text"]["text"], "⭐⭐⭐⭐");
+        assert_eq!(blocks[2]["text"]["text"], "Geweldige koffie");
+        assert_eq!(blocks[3]["type"], "image");
+        assert_eq!(blocks[3]["image_url"], "https://img/1");
+        assert_eq!(
+            blocks[4]["elements"][0]["text"],
+            "Review by <https://www.google.com/maps/contrib/gmaps-42/reviews?hl=en|Alice> · <https://example.com/review|View on Google Maps>"
+        );
+    }
+
+    #[test]
+    fn build_slack_message_skips_image_without_pictures() {
+        let mut event = sample_event();
+        event.review.review.pictures = serde_json::json!([]);
+        let message = build_slack_message(&event, false, "*");
+
+        let blocks = message["blocks"].as_array().unwrap();
+        assert_eq!(blocks[2]["text"]["text"], "Great coffee");
+        assert!(blocks.iter().all(|block| block["type"] != "image"));
+    }
+
+    #[test]
+    fn escape_mrkdwn_escapes_control_characters() {
+        assert_eq!(escape_mrkdwn("<b> & <@U123>"), "&lt;b&gt; &amp; &lt;@U123&gt;");
+    }
+
+    #[test]
+    fn truncate_adds_ellipsis() {
+        assert_eq!(truncate("abcdef", 4), "abc…");
+        assert_eq!(truncate("abc", 4), "abc");
+    }
+
+    #[tokio::test]
+    async fn send_posts_message_to_webhook() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/services/T000/B000/XXX"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let notifier = SlackNotifier::new(format!("{}/services/T000/B000/XXX", server.uri()), true);
+        let message = build_slack_message(&sample_event(), true, "⭐");
+        notifier.send(&message).await.unwrap();
+
+        let request = &server.received_requests().await.unwrap()[0];
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(body, message);
+    }
+
+    #[tokio::test]
+    async fn send_fails_when_slack_rejects_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("invalid_blocks"))
+            .mount(&server)
+            .await;
+
+        let notifier = SlackNotifier::new(server.uri(), true);
+        let err = notifier.send(&serde_json::json!({})).await.unwrap_err();
+        assert!(err.to_string().contains("invalid_blocks"));
+    }
+}
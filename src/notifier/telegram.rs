@@ -0,0 +1,343 @@
+use crate::config::get_config;
+use crate::crawler::pages::user::GMAPS_USER_URL;
+use crate::models::Sink;
+use crate::notifier::{Notifier, ReviewEvent};
+use crate::utility;
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+pub const SINK_KIND: &str = "telegram";
+static TELEGRAM_API_URL: &str = "https://api.telegram.org";
+/// Telegram rejects photo captions longer than this.
+const CAPTION_LIMIT: usize = 1024;
+/// Leaves room for the escaped header and footer within Telegram's 4096 character message limit.
+const BODY_LIMIT: usize = 3000;
+/// Telegram albums hold at most this many photos.
+const ALBUM_LIMIT: usize = 10;
+
+static TELEGRAM_CLIENT: OnceLock<Client> = OnceLock::new();
+
+#[derive(Deserialize)]
+struct TelegramResponse {
+    ok: bool,
+    description: Option<String>,
+}
+
+fn telegram_client() -> &'static Client {
+    TELEGRAM_CLIENT.get_or_init(|| {
+        Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("failed to build Telegram HTTP client")
+    })
+}
+
+/// Posts reviews to a Telegram chat through the Bot API.
+pub struct TelegramNotifier {
+    api_url: String,
+    bot_token: String,
+    chat_id: String,
+    original: bool,
+}
+
+impl TelegramNotifier {
+    pub fn new(api_url: String, bot_token: String, chat_id: String, original: bool) -> Self {
+        TelegramNotifier {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            bot_token,
+            chat_id,
+            original,
+        }
+    }
+
+    pub fn from_sink(sink: &Sink) -> Result<Self> {
+        let Some(bot_token) = sink.secret.clone() else {
+            return Err(anyhow::anyhow!("Telegram sink {} has no bot token", sink.id));
+        };
+        let api_url = sink
+            .config
+            .get("api_url")
+            .and_then(Value::as_str)
+            .unwrap_or(TELEGRAM_API_URL);
+
+        Ok(TelegramNotifier::new(
+            api_url.to_string(),
+            bot_token,
+            sink.target.clone(),
+            super::sink_original_text(sink),
+        ))
+    }
+
+    async fn deliver(&self, event: &ReviewEvent, star_text: &str) -> Result<()> {
+        let text = build_telegram_text(event, self.original, star_text);
+        let pictures = utility::embed::collect_valid_pictures(&event.review.review.pictures);
+        // Captions are capped far below the message limit, so long reviews follow the album instead.
+        let caption = (text.chars().count() <= CAPTION_LIMIT).then_some(text.as_str());
+
+        match pictures.as_slice() {
+            [] => {
+                return self
+                    .call("sendMessage", json!({
+                        "chat_id": self.chat_id,
+                        "text": text,
+                        "parse_mode": "MarkdownV2",
+                        "disable_web_page_preview": true
+                    }))
+                    .await;
+            }
+            [picture] => {
+                let mut body = json!({ "chat_id": self.chat_id, "photo": picture });
+                if let Some(caption) = caption {
+                    body["caption"] = json!(caption);
+                    body["parse_mode"] = json!("MarkdownV2");
+                }
+                self.call("sendPhoto", body).await?;
+            }
+            pictures => {
+                let media: Vec<Value> = pictures
+                    .iter()
+                    .take(ALBUM_LIMIT)
+                    .enumerate()
+                    .map(|(index, picture)| {
+                        let mut item = json!({ "type": "photo", "media": picture });
+                        if let (0, Some(caption)) = (index, caption) {
+                            item["caption"] = json!(caption);
+                            item["parse_mode"] = json!("MarkdownV2");
+                        }
+                        item
+                    })
+                    .collect();
+                self.call("sendMediaGroup", json!({ "chat_id": self.chat_id, "media": media }))
+                    .await?;
+            }
+        }
+
+        if caption.is_none() {
+            self.call("sendMessage", json!({
+                "chat_id": self.chat_id,
+                "text": text,
+                "parse_mode": "MarkdownV2",
+                "disable_web_page_preview": true
+            }))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn call(&self, method: &str, body: Value) -> Result<()> {
+        let url = format!("{}/bot{}/{}", self.api_url, self.bot_token, method);
+        let resp = match telegram_client().post(url).json(&body).send().await {
+            Ok(resp) => resp,
+            // The request error contains the URL and therefore the bot token.
+            Err(err) => {
+                return Err(anyhow::anyhow!("Failed to send {} request to Telegram: {}", method, err.without_url()));
+            }
+        };
+
+        let status = resp.status();
+        let resp: TelegramResponse = match resp.json().await {
+            Ok(json) => json,
+            Err(_) => return Err(anyhow::anyhow!("Telegram {} responded with: {}", method, status)),
+        };
+        if !resp.ok {
+            return Err(anyhow::anyhow!(
+                "Telegram {} failed: {}",
+                method,
+                resp.description.unwrap_or_else(|| status.to_string())
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    fn target(&self) -> String {
+        format!("telegram chat {}", self.chat_id)
+    }
+
+    fn kind(&self) -> &'static str {
+        SINK_KIND
+    }
+
+    async fn notify(&self, event: &ReviewEvent) -> Result<()> {
+        self.deliver(event, get_config().star_text.as_str()).await
+    }
+}
+
+/// Renders a review as MarkdownV2 text.
+pub fn build_telegram_text(event: &ReviewEvent, original: bool, star_text: &str) -> String {
+    let review = &event.review.review;
+    let user = &event.review.user;
+    let user_url = GMAPS_USER_URL.replace("{}", user.gmaps_id.as_str());
+    let stars = star_text.repeat(review.stars.try_into().unwrap_or_default());
+    let full_body = utility::embed::select_review_body(&event.review, original);
+    let mut body: String = full_body.chars().take(BODY_LIMIT).collect();
+    if body.len() < full_body.len() {
+        body.push('…');
+    }
+
+    let title = match &review.link_en {
+        Some(link) => format!("*[{}]({})*", escape_markdown(review.place_name.as_str()), escape_link(link)),
+        None => format!("*{}*", escape_markdown(review.place_name.as_str())),
+    };
+    let mut text = format!("{}\n{}", title, escape_markdown(stars.as_str()));
+    if !body.trim().is_empty() {
+        text.push_str(format!("\n\n{}", escape_markdown(body.as_str())).as_str());
+    }
+    text.push_str(
+        format!(
+            "\n\n_Review by_ [{}]({})",
+            escape_markdown(user.name.as_str()),
+            escape_link(user_url.as_str())
+        )
+            .as_str(),
+    );
+    text
+}
+
+/// Escapes every character MarkdownV2 reserves outside of entities.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '_' | '*' | '[' | ']' | '(' | ')' | '~' | '`' | '>' | '#' | '+' | '-' | '=' | '|' | '{' | '}' | '.' | '!' | '\\'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Inside link targets MarkdownV2 only reserves `)` and `\`.
+fn escape_link(url: &str) -> String {
+    url.replace('\\', "\\\\").replace(')', "\\)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_telegram_text, escape_link, escape_markdown, TelegramNotifier};
+    use crate::notifier::tests::sample_event;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn ok() -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({ "ok": true, "result": {} }))
+    }
+
+    #[test]
+    fn escape_markdown_escapes_reserved_characters() {
+        assert_eq!(escape_markdown("Café (4.5/5)! #1"), "Café \\(4\\.5/5\\)\\! \\#1");
+        assert_eq!(escape_markdown("a_b*c"), "a\\_b\\*c");
+    }
+
+    #[test]
+    fn escape_link_only_escapes_parenthesis_and_backslash() {
+        assert_eq!(escape_link("https://x.y/a_(b)"), "https://x.y/a_(b\\)");
+    }
+
+    #[test]
+    fn build_telegram_text_formats_review() {
+        let mut event = sample_event();
+        event.review.review.place_name = "Café.Bar".to_string();
+        let text = build_telegram_text(&event, false, "*");
+
+        assert_eq!(
+            text,
+            "*[Café\\.Bar](https://example.com/review)*\n\\*\\*\\*\\*\n\nGreat coffee\n\n\
+             _Review by_ [Alice](https://www.google.com/maps/contrib/gmaps-42/reviews?hl=en)"
+        );
+    }
+
+    #[tokio::test]
+    async fn deliver_sends_album_with_caption() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendMediaGroup"))
+            .and(body_partial_json(serde_json::json!({ "chat_id": "-100" })))
+            .respond_with(ok())
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let notifier = TelegramNotifier::new(server.uri(), "token".to_string(), "-100".to_string(), true);
+        notifier.deliver(&sample_event(), "⭐").await.unwrap();
+
+        let request = &server.received_requests().await.unwrap()[0];
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        let media = body["media"].as_array().unwrap();
+        assert_eq!(media.len(), 2);
+        assert_eq!(media[0]["media"], "https://img/1");
+        assert_eq!(media[0]["parse_mode"], "MarkdownV2");
+        assert!(media[1].get("caption").is_none());
+    }
+
+    #[tokio::test]
+    async fn deliver_sends_plain_message_without_pictures() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendMessage"))
+            .and(body_partial_json(serde_json::json!({ "parse_mode": "MarkdownV2" })))
+            .respond_with(ok())
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut event = sample_event();
+        event.review.review.pictures = serde_json::json!([]);
+        let notifier = TelegramNotifier::new(server.uri(), "token".to_string(), "-100".to_string(), true);
+        notifier.deliver(&event, "⭐").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn deliver_sends_long_review_after_photo() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendPhoto"))
+            .respond_with(ok())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendMessage"))
+            .respond_with(ok())
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut event = sample_event();
+        event.review.review.pictures = serde_json::json!(["https://img/1"]);
+        event.review.review.original_text = Some("Lovely. ".repeat(200));
+        let notifier = TelegramNotifier::new(server.uri(), "token".to_string(), "-100".to_string(), true);
+        notifier.deliver(&event, "⭐").await.unwrap();
+
+        let photo = &server.received_requests().await.unwrap()[0];
+        let body: serde_json::Value = serde_json::from_slice(&photo.body).unwrap();
+        assert!(body.get("caption").is_none());
+    }
+
+    #[tokio::test]
+    async fn deliver_reports_api_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "ok": false,
+                "description": "Bad Request: chat not found"
+            })))
+            .mount(&server)
+            .await;
+
+        let mut event = sample_event();
+        event.review.review.pictures = serde_json::json!([]);
+        let notifier = TelegramNotifier::new(server.uri(), "token".to_string(), "-100".to_string(), true);
+        let err = notifier.deliver(&event, "⭐").await.unwrap_err();
+        assert!(err.to_string().contains("chat not found"));
+    }
+}
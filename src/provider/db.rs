@@ -24,7 +24,7 @@ impl DbProvider {
     }
 
     pub fn global() -> &'static DbProvider {
-        DB_INSTANCE.get_or_init(|| DbProvider::new())
+        DB_INSTANCE.get_or_init(DbProvider::new)
     }
 
     pub fn get_connection(&self) -> Result<DbConnection> {
@@ -0,0 +1,178 @@
+use crate::models::{ChannelSettings, DigestEntry, NewDigestEntry};
+use crate::provider::db::DbConnection;
+use crate::schema::channel_settings;
+use crate::schema::digest_queue;
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+pub fn get_channel_settings(channel: &str) -> Result<Option<ChannelSettings>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match channel_settings::table
+        .filter(channel_settings::channel_id.eq(channel))
+        .first::<ChannelSettings>(&mut conn)
+        .optional()
+    {
+        Ok(settings) => Ok(settings),
+        Err(e) => {
+            tracing::error!("Failed to load channel settings for {}: {}", channel, e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn get_all_channel_settings() -> Result<Vec<ChannelSettings>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match channel_settings::table.load::<ChannelSettings>(&mut conn) {
+        Ok(settings) => Ok(settings),
+        Err(e) => {
+            tracing::error!("Failed to load channel settings: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn save_channel_settings(settings: &ChannelSettings) -> Result<ChannelSettings> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::insert_into(channel_settings::table)
+        .values(settings)
+        .on_conflict(channel_settings::channel_id)
+        .do_update()
+        .set(settings)
+        .get_result::<ChannelSettings>(&mut conn)
+    {
+        Ok(saved) => Ok(saved),
+        Err(e) => {
+            tracing::error!("Failed to save channel settings: {}", e);
+            Err(anyhow::anyhow!("Database insert error: {}", e))
+        }
+    }
+}
+
+pub fn mark_digest_sent(channel: &str, sent_at: NaiveDateTime) -> Result<()> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::update(channel_settings::table.filter(channel_settings::channel_id.eq(channel)))
+        .set(channel_settings::last_digest_at.eq(sent_at))
+        .execute(&mut conn)
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Failed to mark digest as sent for {}: {}", channel, e);
+            Err(anyhow::anyhow!("Database update error: {}", e))
+        }
+    }
+}
+
+pub fn queue_review(entry: &NewDigestEntry) -> Result<()> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::insert_into(digest_queue::table)
+        .values(entry)
+        .execute(&mut conn)
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Failed to queue review for digest: {}", e);
+            Err(anyhow::anyhow!("Database insert error: {}", e))
+        }
+    }
+}
+
+pub fn get_queued_reviews(channel: &str) -> Result<Vec<DigestEntry>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match digest_queue::table
+        .filter(digest_queue::channel_id.eq(channel))
+        .order(digest_queue::queued_at.asc())
+        .load::<DigestEntry>(&mut conn)
+    {
+        Ok(entries) => Ok(entries),
+        Err(e) => {
+            tracing::error!("Failed to load queued reviews for {}: {}", channel, e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn delete_queued_reviews(ids: &[i32]) -> Result<()> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::delete(digest_queue::table.filter(digest_queue::id.eq_any(ids))).execute(&mut conn) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Failed to delete queued reviews: {}", e);
+            Err(anyhow::anyhow!("Database delete error: {}", e))
+        }
+    }
+}
+
+/// Drops the delivery settings and pending digest of a channel or sink.
+pub fn delete_channel_digest(channel: &str) -> Result<()> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    let result = conn.transaction(|conn| {
+        diesel::delete(digest_queue::table.filter(digest_queue::channel_id.eq(channel))).execute(conn)?;
+        diesel::delete(channel_settings::table.filter(channel_settings::channel_id.eq(channel))).execute(conn)
+    });
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Failed to delete digest settings for {}: {}", channel, e);
+            Err(anyhow::anyhow!("Database delete error: {}", e))
+        }
+    }
+}
+
+fn get_connection() -> Option<DbConnection> {
+    match crate::provider::db::DbProvider::global().get_connection() {
+        Ok(c) => Some(c),
+        Err(e) => {
+            tracing::error!("Failed to get DB connection: {}", e);
+            None
+        }
+    }
+}
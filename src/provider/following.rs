@@ -4,6 +4,7 @@ use crate::provider::db::DbConnection;
 use crate::schema::following;
 use crate::schema::reviews;
 use crate::schema::users;
+use crate::utility::mention::Mentions;
 use anyhow::Result;
 use chrono::Utc;
 use diesel::prelude::*;
@@ -31,6 +32,23 @@ pub fn get_amount_of_users_followed() -> Result<i64> {
     }
 }
 
+pub fn get_amount_of_followings() -> Result<i64> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match following::table.count().get_result::<i64>(&mut conn) {
+        Ok(count) => Ok(count),
+        Err(e) => {
+            tracing::error!("Failed to count followings: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
 pub fn get_followed_users_with_old_reviews() -> Result<Vec<User>> {
     let mut conn = match get_connection() {
         Some(c) => c,
@@ -39,7 +57,7 @@ pub fn get_followed_users_with_old_reviews() -> Result<Vec<User>> {
         }
     };
 
-    let age_limit_hours = get_config().review_age_limit_hours;
+    let age_limit_hours = get_config().scheduler.review_age_limit_hours;
     let age_limit_duration = chrono::Duration::hours(age_limit_hours);
     let cutoff_time = (Utc::now() - age_limit_duration).naive_utc();
 
@@ -83,6 +101,70 @@ pub fn get_followers_of_user(user_id: i32) -> Result<Vec<Following>> {
     }
 }
 
+pub fn get_followings_in_channel(channel: String) -> Result<Vec<Following>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match following::table
+        .filter(following::channel_id.eq(channel))
+        .load::<Following>(&mut conn)
+    {
+        Ok(followings) => Ok(followings),
+        Err(e) => {
+            tracing::error!("Failed to load followings in channel: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn get_all_followings() -> Result<Vec<(Following, User)>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match following::table
+        .inner_join(users::table.on(users::id.eq(following::followed_user_id)))
+        .order((following::channel_id.asc(), users::name.asc()))
+        .select((following::all_columns, users::all_columns))
+        .load::<(Following, User)>(&mut conn)
+    {
+        Ok(followings) => Ok(followings),
+        Err(e) => {
+            tracing::error!("Failed to load followings: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn get_followings_with_users_in_channel(channel: String) -> Result<Vec<(Following, User)>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match following::table
+        .inner_join(users::table.on(users::id.eq(following::followed_user_id)))
+        .filter(following::channel_id.eq(channel))
+        .select((following::all_columns, users::all_columns))
+        .load::<(Following, User)>(&mut conn)
+    {
+        Ok(followings) => Ok(followings),
+        Err(e) => {
+            tracing::error!("Failed to load followings in channel: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
 pub fn get_users_followed_in_channel(channel: String) -> Result<Vec<User>> {
     let mut conn = match get_connection() {
         Some(c) => c,
@@ -105,12 +187,11 @@ pub fn get_users_followed_in_channel(channel: String) -> Result<Vec<User>> {
     }
 }
 
-pub fn is_user_followed_in_channel(user_id: i32, channel: String) -> bool {
+pub fn get_following_in_channel(user_id: i32, channel: String) -> Result<Option<Following>> {
     let mut conn = match get_connection() {
         Some(c) => c,
         None => {
-            tracing::error!("Failed to get DB connection");
-            return false;
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
         }
     };
 
@@ -118,21 +199,26 @@ pub fn is_user_followed_in_channel(user_id: i32, channel: String) -> bool {
         .filter(following::followed_user_id.eq(user_id))
         .filter(following::channel_id.eq(channel))
         .first::<Following>(&mut conn)
+        .optional()
     {
-        Ok(_) => true,
-        Err(diesel::result::Error::NotFound) => false,
+        Ok(following) => Ok(following),
         Err(e) => {
-            tracing::error!("Database query error: {}", e);
-            false
+            tracing::error!("Failed to load following in channel: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn follow_user_in_channel(
     user_id: i32,
     channel: String,
     original_text: bool,
     webhook: String,
+    mentions: &Mentions,
+    thread_per_user: bool,
+    forum: bool,
+    language: Option<String>,
 ) -> Result<Following> {
     let mut conn = match get_connection() {
         Some(c) => c,
@@ -146,6 +232,12 @@ pub fn follow_user_in_channel(
         channel_id: channel,
         original_text,
         webhook_id: webhook,
+        mention_role_id: mentions.role_id.clone(),
+        mention_user_ids: serde_json::to_value(&mentions.user_ids)?,
+        mention_here: mentions.here,
+        thread_per_user,
+        forum,
+        language,
     };
 
     match diesel::insert_into(following::table)
@@ -203,6 +295,94 @@ pub fn update_webhook(webhook: &str, channel_id: &str) -> Result<()> {
     }
 }
 
+pub fn update_mentions(user_id: i32, channel: String, mentions: &Mentions) -> Result<()> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::update(
+        following::table
+            .filter(following::followed_user_id.eq(user_id))
+            .filter(following::channel_id.eq(channel)),
+    )
+        .set((
+            following::mention_role_id.eq(mentions.role_id.clone()),
+            following::mention_user_ids.eq(serde_json::to_value(&mentions.user_ids)?),
+            following::mention_here.eq(mentions.here),
+        ))
+        .execute(&mut conn)
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Failed to update mentions: {}", e);
+            Err(anyhow::anyhow!("Database update error: {}", e))
+        }
+    }
+}
+
+pub fn update_thread(following_id: i32, thread_id: &str) -> Result<()> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::update(following::table.filter(following::id.eq(following_id)))
+        .set(following::thread_id.eq(thread_id))
+        .execute(&mut conn)
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Failed to update thread: {}", e);
+            Err(anyhow::anyhow!("Database update error: {}", e))
+        }
+    }
+}
+
+pub fn update_thread_per_user(following_id: i32, thread_per_user: bool) -> Result<()> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::update(following::table.filter(following::id.eq(following_id)))
+        .set(following::thread_per_user.eq(thread_per_user))
+        .execute(&mut conn)
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Failed to update thread mode: {}", e);
+            Err(anyhow::anyhow!("Database update error: {}", e))
+        }
+    }
+}
+
+pub fn update_language(following_id: i32, language: Option<String>) -> Result<()> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::update(following::table.filter(following::id.eq(following_id)))
+        .set(following::language.eq(language))
+        .execute(&mut conn)
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Failed to update language: {}", e);
+            Err(anyhow::anyhow!("Database update error: {}", e))
+        }
+    }
+}
+
 fn get_connection() -> Option<DbConnection> {
     match crate::provider::db::DbProvider::global().get_connection() {
         Ok(c) => Some(c),
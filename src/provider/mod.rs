@@ -1,4 +1,9 @@
 pub mod db;
+pub mod digest;
 pub(crate) mod following;
 pub mod review;
+pub mod short_link;
+pub mod short_url;
+pub mod sink;
+pub mod subscription;
 pub mod user;
@@ -1,16 +1,29 @@
 use crate::models::{NewReview, Review, ReviewWithUser, User};
 use crate::provider::db::DbConnection;
 use crate::provider::user::{get_user_from_db_id, gmaps_user_id_to_db_id};
+use crate::schema::following;
 use crate::schema::reviews;
 use crate::schema::users;
+use crate::utility::metrics::{self, CrawlOutcome};
 use crate::utility::shorten::shorten_url;
 use diesel::prelude::*;
+use futures::stream::{self, StreamExt};
 use reqwest::Url;
 
+/// Older reviews are kept for feeds; anything beyond this many per user is pruned.
+const REVIEW_HISTORY_LIMIT: i64 = 20;
+/// How many picture URLs of one review are shortened at the same time.
+const PICTURE_SHORTEN_CONCURRENCY: usize = 4;
+
 pub fn get_latest_review_for_user_gmaps_id(gmaps_id: &str) -> Option<ReviewWithUser> {
     get_latest_review_for_user(gmaps_user_id_to_db_id(gmaps_id)?)
 }
 
+#[tracing::instrument(
+    name = "crawl",
+    skip_all,
+    fields(user_id = user.id, gmaps_id = %user.gmaps_id, outcome = tracing::field::Empty, duration_ms = tracing::field::Empty)
+)]
 pub fn check_for_new_review(user: &User) -> Option<ReviewWithUser> {
     let Some(old_review) = get_latest_review_from_db(user.id) else { return fetch_and_save_latest_review(user) };
     if !is_review_past_age_limit(&old_review.review) {
@@ -19,18 +32,67 @@ pub fn check_for_new_review(user: &User) -> Option<ReviewWithUser> {
 
     let latest_review = fetch_latest_review(user)?;
     if is_new_review_different(&old_review.review, &latest_review) {
+        record_crawl(CrawlOutcome::New);
         save_new_review(&latest_review)
     } else {
+        record_crawl(CrawlOutcome::Unchanged);
         None
     }
 }
 
+/// What `check_for_new_review` would find for a user.
+pub enum ReviewPreview {
+    /// The stored review is still within the age limit, so the user isn't crawled.
+    NotDue,
+    CrawlFailed,
+    /// The crawled review matches the stored one.
+    Unchanged,
+    /// The crawled review would be stored and delivered; it has no ID and unshortened URLs.
+    New(Box<ReviewWithUser>),
+}
+
+/// Runs the same checks as `check_for_new_review` without shortening or storing anything.
+#[tracing::instrument(
+    name = "crawl",
+    skip_all,
+    fields(user_id = user.id, gmaps_id = %user.gmaps_id, outcome = tracing::field::Empty, duration_ms = tracing::field::Empty)
+)]
+pub fn preview_new_review(user: &User) -> ReviewPreview {
+    let old_review = get_latest_review_from_db(user.id);
+    if old_review.as_ref().is_some_and(|old| !is_review_past_age_limit(&old.review)) {
+        return ReviewPreview::NotDue;
+    }
+
+    let Some(latest_review) = fetch_latest_review(user) else { return ReviewPreview::CrawlFailed };
+    if old_review.is_some_and(|old| !is_new_review_different(&old.review, &latest_review)) {
+        record_crawl(CrawlOutcome::Unchanged);
+        return ReviewPreview::Unchanged;
+    }
+
+    record_crawl(CrawlOutcome::New);
+    ReviewPreview::New(Box::new(ReviewWithUser {
+        user: user.clone(),
+        review: Review {
+            id: 0,
+            place_name: latest_review.place_name,
+            text: latest_review.text,
+            original_text: latest_review.original_text,
+            stars: latest_review.stars,
+            user_id: latest_review.user_id,
+            found_at: chrono::Utc::now().naive_utc(),
+            link_en: Some(latest_review.link_en),
+            pictures: latest_review.pictures,
+            language: latest_review.language,
+        },
+    }))
+}
+
 pub fn get_latest_review_for_user(user_id: i32) -> Option<ReviewWithUser> {
     let latest_in_db = get_latest_review_from_db(user_id);
-    if let Some(latest) = latest_in_db.as_ref() {
-        if !is_review_past_age_limit(&latest.review) {
-            return latest_in_db;
-        }
+    if let Some(latest) = latest_in_db.as_ref()
+        && !is_review_past_age_limit(&latest.review)
+    {
+        return latest_in_db;
     }
 
     let Some(user) = get_user_from_db_id(user_id) else {
@@ -44,6 +106,61 @@ pub fn get_latest_review_for_user(user_id: i32) -> Option<ReviewWithUser> {
     }
 }
 
+pub fn get_review_with_user_by_id(review_id: i32) -> Option<ReviewWithUser> {
+    let mut conn = get_connection()?;
+
+    users::table
+        .inner_join(reviews::table)
+        .filter(reviews::id.eq(review_id))
+        .first::<(User, Review)>(&mut conn)
+        .optional()
+        .unwrap_or_else(|e| {
+            tracing::error!("Database query error: {}", e);
+            None
+        })
+        .map(|(user, review)| ReviewWithUser { user, review })
+}
+
+/// The newest stored reviews of a user, without crawling when the user is unknown.
+pub fn get_recent_reviews_for_gmaps_id(gmaps_id: &str, limit: i64) -> Option<Vec<ReviewWithUser>> {
+    let mut conn = get_connection()?;
+
+    users::table
+        .inner_join(reviews::table)
+        .filter(users::gmaps_id.eq(gmaps_id))
+        .order(reviews::found_at.desc())
+        .limit(limit)
+        .load::<(User, Review)>(&mut conn)
+        .map(|rows| {
+            rows.into_iter()
+                .map(|(user, review)| ReviewWithUser { user, review })
+                .collect()
+        })
+        .map_err(|e| tracing::error!("Database query error: {}", e))
+        .ok()
+}
+
+/// The newest stored reviews of every user followed in a channel.
+pub fn get_recent_reviews_in_channel(channel_id: &str, limit: i64) -> Option<Vec<ReviewWithUser>> {
+    let mut conn = get_connection()?;
+
+    users::table
+        .inner_join(reviews::table)
+        .inner_join(following::table.on(following::followed_user_id.eq(users::id)))
+        .filter(following::channel_id.eq(channel_id))
+        .order(reviews::found_at.desc())
+        .limit(limit)
+        .select((users::all_columns, reviews::all_columns))
+        .load::<(User, Review)>(&mut conn)
+        .map(|rows| {
+            rows.into_iter()
+                .map(|(user, review)| ReviewWithUser { user, review })
+                .collect()
+        })
+        .map_err(|e| tracing::error!("Database query error: {}", e))
+        .ok()
+}
+
 fn get_latest_review_from_db(user_id: i32) -> Option<ReviewWithUser> {
     let mut conn = get_connection()?;
 
@@ -58,19 +175,34 @@ fn get_latest_review_from_db(user_id: i32) -> Option<ReviewWithUser> {
 
 fn fetch_and_save_latest_review(user: &User) -> Option<ReviewWithUser> {
     let new_review = fetch_latest_review(user)?;
+    record_crawl(CrawlOutcome::New);
     save_new_review(&new_review)
 }
 
 fn fetch_latest_review(user: &User) -> Option<NewReview> {
-    match crate::crawler::pages::review::get_latest_review_for_user(user) {
+    let started = std::time::Instant::now();
+    let result = crate::crawler::pages::review::get_latest_review_for_user(user);
+    let duration = started.elapsed();
+    metrics::record_crawl_duration(duration);
+    tracing::Span::current().record("duration_ms", duration.as_millis() as u64);
+
+    match result {
         Ok(r) => Some(r),
         Err(e) => {
             tracing::error!("Failed to fetch latest review from Google Maps: {}", e);
+            record_crawl(CrawlOutcome::Failed);
             None
         }
     }
 }
 
+/// Counts the crawl and notes its outcome on the surrounding crawl span.
+fn record_crawl(outcome: CrawlOutcome) {
+    metrics::record_crawl(outcome);
+    tracing::Span::current().record("outcome", outcome.as_str());
+    tracing::info!(outcome = outcome.as_str(), "Crawl finished");
+}
+
 fn save_new_review(new_review: &NewReview) -> Option<ReviewWithUser> {
     let mut conn = get_connection()?;
 
@@ -106,7 +238,17 @@ fn save_new_review(new_review: &NewReview) -> Option<ReviewWithUser> {
     modified_review.pictures = shortened_pictures;
 
     match conn.transaction::<_, diesel::result::Error, _>(|conn| {
-        diesel::delete(reviews::table.filter(reviews::user_id.eq(modified_review.user_id)))
+        let kept_ids = reviews::table
+            .filter(reviews::user_id.eq(modified_review.user_id))
+            .order(reviews::found_at.desc())
+            .limit(REVIEW_HISTORY_LIMIT - 1)
+            .select(reviews::id)
+            .load::<i32>(conn)?;
+        diesel::delete(
+            reviews::table
+                .filter(reviews::user_id.eq(modified_review.user_id))
+                .filter(diesel::dsl::not(reviews::id.eq_any(kept_ids))),
+        )
             .execute(conn)?;
 
         diesel::insert_into(reviews::table)
@@ -146,7 +288,7 @@ fn get_connection() -> Option<DbConnection> {
 }
 
 fn is_review_past_age_limit(review: &Review) -> bool {
-    let age_limit_hours = crate::config::get_config().review_age_limit_hours;
+    let age_limit_hours = crate::config::get_config().scheduler.review_age_limit_hours;
     let age_limit_duration = chrono::Duration::hours(age_limit_hours);
     let cutoff_time = (chrono::Utc::now() - age_limit_duration).naive_utc();
     review.found_at < cutoff_time
@@ -225,34 +367,37 @@ fn extract_picture_count(pictures: &serde_json::Value) -> usize {
 async fn shorten_picture_urls_async(pictures: &serde_json::Value) -> serde_json::Value {
     match pictures.as_array() {
         Some(arr) => {
-            let mut shortened_urls = Vec::new();
-            for v in arr.iter() {
-                if let Some(url_str) = v.as_str() {
-                    match Url::parse(url_str) {
-                        Ok(url) => match shorten_url(&url).await {
-                            Ok(shortened) => {
-                                shortened_urls.push(serde_json::Value::String(shortened));
-                            }
-                            Err(e) => {
-                                tracing::warn!("Failed to shorten picture URL: {}, using original URL", e);
-                                shortened_urls.push(serde_json::Value::String(url_str.to_string()));
-                            }
-                        },
-                        Err(e) => {
-                            tracing::warn!("Failed to parse picture URL: {}, using original URL", e);
-                            shortened_urls.push(serde_json::Value::String(url_str.to_string()));
-                        }
-                    }
-                } else {
-                    shortened_urls.push(v.clone());
-                }
-            }
+            let shortened_urls = stream::iter(arr.iter())
+                .map(shorten_picture_url)
+                .buffered(PICTURE_SHORTEN_CONCURRENCY)
+                .collect::<Vec<_>>()
+                .await;
             serde_json::Value::Array(shortened_urls)
         }
         None => pictures.clone(),
     }
 }
 
+async fn shorten_picture_url(picture: &serde_json::Value) -> serde_json::Value {
+    let Some(url_str) = picture.as_str() else {
+        return picture.clone();
+    };
+
+    match Url::parse(url_str) {
+        Ok(url) => match shorten_url(&url).await {
+            Ok(shortened) => serde_json::Value::String(shortened),
+            Err(e) => {
+                tracing::warn!("Failed to shorten picture URL: {}, using original URL", e);
+                serde_json::Value::String(url_str.to_string())
+            }
+        },
+        Err(e) => {
+            tracing::warn!("Failed to parse picture URL: {}, using original URL", e);
+            serde_json::Value::String(url_str.to_string())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{extract_picture_count, is_new_review_different, shorten_picture_urls_async};
@@ -271,6 +416,7 @@ mod tests {
             found_at: Utc::now().naive_utc(),
             link_en: Some("https://example.com".to_string()),
             pictures,
+            language: None,
         }
     }
 
@@ -287,6 +433,7 @@ mod tests {
             user_id: 42,
             link_en: "https://example.com/new".to_string(),
             pictures,
+            language: None,
         }
     }
 
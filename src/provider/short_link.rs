@@ -0,0 +1,62 @@
+use crate::models::{NewShortLink, ShortLink};
+use crate::provider::db::DbConnection;
+use crate::schema::short_links;
+use anyhow::Result;
+use diesel::prelude::*;
+
+/// Returns the id of the short link for `url`, creating it when the URL wasn't shortened before.
+pub fn get_or_create_short_link(url: &str) -> Result<i32> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    let new_link = NewShortLink { url: url.to_string() };
+    match diesel::insert_into(short_links::table)
+        .values(&new_link)
+        .on_conflict(short_links::url)
+        .do_update()
+        .set(short_links::url.eq(url))
+        .returning(short_links::id)
+        .get_result::<i32>(&mut conn)
+    {
+        Ok(id) => Ok(id),
+        Err(e) => {
+            tracing::error!("Failed to save short link: {}", e);
+            Err(anyhow::anyhow!("Database insert error: {}", e))
+        }
+    }
+}
+
+pub fn get_short_link(id: i32) -> Result<Option<ShortLink>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match short_links::table
+        .find(id)
+        .first::<ShortLink>(&mut conn)
+        .optional()
+    {
+        Ok(link) => Ok(link),
+        Err(e) => {
+            tracing::error!("Failed to load short link {}: {}", id, e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+fn get_connection() -> Option<DbConnection> {
+    match crate::provider::db::DbProvider::global().get_connection() {
+        Ok(c) => Some(c),
+        Err(e) => {
+            tracing::error!("Failed to get DB connection: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::models::NewShortUrl;
+use crate::provider::db::DbConnection;
+use crate::schema::short_urls;
+use anyhow::Result;
+use diesel::prelude::*;
+
+/// The short URL the given shortener returned for the URL earlier.
+pub fn get_short_url(shortener: &str, original_url: &str) -> Result<Option<String>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match short_urls::table
+        .find((shortener, original_url))
+        .select(short_urls::short_url)
+        .first::<String>(&mut conn)
+        .optional()
+    {
+        Ok(short_url) => Ok(short_url),
+        Err(e) => {
+            tracing::error!("Failed to load short URL for {}: {}", original_url, e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn save_short_url(shortener: &str, original_url: &str, short_url: &str) -> Result<()> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    let new_short_url = NewShortUrl {
+        shortener: shortener.to_string(),
+        original_url: original_url.to_string(),
+        short_url: short_url.to_string(),
+    };
+    match diesel::insert_into(short_urls::table)
+        .values(&new_short_url)
+        .on_conflict((short_urls::shortener, short_urls::original_url))
+        .do_update()
+        .set(&new_short_url)
+        .execute(&mut conn)
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Failed to save short URL: {}", e);
+            Err(anyhow::anyhow!("Database insert error: {}", e))
+        }
+    }
+}
+
+fn get_connection() -> Option<DbConnection> {
+    match crate::provider::db::DbProvider::global().get_connection() {
+        Ok(c) => Some(c),
+        Err(e) => {
+            tracing::error!("Failed to get DB connection: {}", e);
+            None
+        }
+    }
+}
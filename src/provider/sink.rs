@@ -0,0 +1,167 @@
+use crate::config::get_config;
+use crate::models::{NewSink, Sink, User};
+use crate::provider::db::DbConnection;
+use crate::schema::reviews;
+use crate::schema::sinks;
+use crate::schema::users;
+use anyhow::Result;
+use chrono::Utc;
+use diesel::prelude::*;
+
+pub fn get_sinked_users_with_old_reviews() -> Result<Vec<User>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    let age_limit_hours = get_config().scheduler.review_age_limit_hours;
+    let age_limit_duration = chrono::Duration::hours(age_limit_hours);
+    let cutoff_time = (Utc::now() - age_limit_duration).naive_utc();
+
+    match sinks::table
+        .inner_join(users::table.on(users::id.eq(sinks::followed_user_id)))
+        .left_join(reviews::table.on(reviews::user_id.eq(users::id)))
+        .filter(
+            reviews::found_at
+                .lt(cutoff_time)
+                .or(reviews::found_at.is_null()),
+        )
+        .select(users::all_columns)
+        .distinct()
+        .load::<User>(&mut conn)
+    {
+        Ok(users) => Ok(users),
+        Err(e) => {
+            tracing::error!("Failed to load users with sinks and old reviews: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn get_amount_of_sinks() -> Result<i64> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match sinks::table.count().get_result::<i64>(&mut conn) {
+        Ok(count) => Ok(count),
+        Err(e) => {
+            tracing::error!("Failed to count sinks: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn get_sinks_of_user(user_id: i32) -> Result<Vec<Sink>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match sinks::table
+        .filter(sinks::followed_user_id.eq(user_id))
+        .load::<Sink>(&mut conn)
+    {
+        Ok(sinks) => Ok(sinks),
+        Err(e) => {
+            tracing::error!("Failed to load sinks for user {}: {}", user_id, e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn get_sink(sink_id: i32) -> Result<Option<Sink>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match sinks::table
+        .filter(sinks::id.eq(sink_id))
+        .first::<Sink>(&mut conn)
+        .optional()
+    {
+        Ok(sink) => Ok(sink),
+        Err(e) => {
+            tracing::error!("Failed to load sink {}: {}", sink_id, e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn get_all_sinks() -> Result<Vec<(Sink, User)>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match sinks::table
+        .inner_join(users::table)
+        .order(sinks::id.asc())
+        .load::<(Sink, User)>(&mut conn)
+    {
+        Ok(sinks) => Ok(sinks),
+        Err(e) => {
+            tracing::error!("Failed to load sinks: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn add_sink(new_sink: &NewSink) -> Result<Sink> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::insert_into(sinks::table)
+        .values(new_sink)
+        .get_result::<Sink>(&mut conn)
+    {
+        Ok(sink) => Ok(sink),
+        Err(e) => {
+            tracing::error!("Failed to add sink: {}", e);
+            Err(anyhow::anyhow!("Database insert error: {}", e))
+        }
+    }
+}
+
+pub fn remove_sink(sink_id: i32) -> Result<Sink> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::delete(sinks::table.filter(sinks::id.eq(sink_id))).get_result::<Sink>(&mut conn) {
+        Ok(deleted) => Ok(deleted),
+        Err(e) => {
+            tracing::error!("Failed to remove sink {}: {}", sink_id, e);
+            Err(anyhow::anyhow!("Database delete error: {}", e))
+        }
+    }
+}
+
+fn get_connection() -> Option<DbConnection> {
+    match crate::provider::db::DbProvider::global().get_connection() {
+        Ok(c) => Some(c),
+        Err(e) => {
+            tracing::error!("Failed to get DB connection: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use crate::config::get_config;
+use crate::models::{NewSubscription, Subscription, User};
+use crate::provider::db::DbConnection;
+use crate::schema::reviews;
+use crate::schema::subscriptions;
+use crate::schema::users;
+use anyhow::Result;
+use chrono::Utc;
+use diesel::prelude::*;
+
+pub fn get_subscribed_users_with_old_reviews() -> Result<Vec<User>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    let age_limit_hours = get_config().scheduler.review_age_limit_hours;
+    let age_limit_duration = chrono::Duration::hours(age_limit_hours);
+    let cutoff_time = (Utc::now() - age_limit_duration).naive_utc();
+
+    match subscriptions::table
+        .inner_join(users::table.on(users::id.eq(subscriptions::followed_user_id)))
+        .left_join(reviews::table.on(reviews::user_id.eq(users::id)))
+        .filter(
+            reviews::found_at
+                .lt(cutoff_time)
+                .or(reviews::found_at.is_null()),
+        )
+        .select(users::all_columns)
+        .distinct()
+        .load::<User>(&mut conn)
+    {
+        Ok(users) => Ok(users),
+        Err(e) => {
+            tracing::error!("Failed to load subscribed users with old reviews: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn get_amount_of_subscriptions() -> Result<i64> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match subscriptions::table.count().get_result::<i64>(&mut conn) {
+        Ok(count) => Ok(count),
+        Err(e) => {
+            tracing::error!("Failed to count subscriptions: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn get_subscribers_of_user(user_id: i32) -> Result<Vec<Subscription>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match subscriptions::table
+        .filter(subscriptions::followed_user_id.eq(user_id))
+        .load::<Subscription>(&mut conn)
+    {
+        Ok(subscriptions) => Ok(subscriptions),
+        Err(e) => {
+            tracing::error!("Failed to load subscriptions for user {}: {}", user_id, e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn get_users_subscribed_by(discord_user_id: String) -> Result<Vec<User>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match subscriptions::table
+        .inner_join(users::table.on(users::id.eq(subscriptions::followed_user_id)))
+        .filter(subscriptions::discord_user_id.eq(discord_user_id))
+        .select(users::all_columns)
+        .load::<User>(&mut conn)
+    {
+        Ok(users) => Ok(users),
+        Err(e) => {
+            tracing::error!("Failed to load users subscribed by discord user: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn get_subscription(user_id: i32, discord_user_id: String) -> Result<Option<Subscription>> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match subscriptions::table
+        .filter(subscriptions::followed_user_id.eq(user_id))
+        .filter(subscriptions::discord_user_id.eq(discord_user_id))
+        .first::<Subscription>(&mut conn)
+        .optional()
+    {
+        Ok(subscription) => Ok(subscription),
+        Err(e) => {
+            tracing::error!("Failed to load subscription: {}", e);
+            Err(anyhow::anyhow!("Database query error: {}", e))
+        }
+    }
+}
+
+pub fn is_subscribed(user_id: i32, discord_user_id: String) -> bool {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            tracing::error!("Failed to get DB connection");
+            return false;
+        }
+    };
+
+    match subscriptions::table
+        .filter(subscriptions::followed_user_id.eq(user_id))
+        .filter(subscriptions::discord_user_id.eq(discord_user_id))
+        .first::<Subscription>(&mut conn)
+    {
+        Ok(_) => true,
+        Err(diesel::result::Error::NotFound) => false,
+        Err(e) => {
+            tracing::error!("Database query error: {}", e);
+            false
+        }
+    }
+}
+
+pub fn subscribe(user_id: i32, discord_user_id: String, original_text: bool, language: Option<String>) -> Result<Subscription> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    let new_subscription = NewSubscription {
+        followed_user_id: user_id,
+        discord_user_id,
+        original_text,
+        language,
+    };
+
+    match diesel::insert_into(subscriptions::table)
+        .values(&new_subscription)
+        .get_result::<Subscription>(&mut conn)
+    {
+        Ok(subscription) => Ok(subscription),
+        Err(e) => {
+            tracing::error!("Failed to subscribe to user: {}", e);
+            Err(anyhow::anyhow!("Database insert error: {}", e))
+        }
+    }
+}
+
+pub fn update_language(user_id: i32, discord_user_id: String, language: Option<String>) -> Result<()> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::update(
+        subscriptions::table
+            .filter(subscriptions::followed_user_id.eq(user_id))
+            .filter(subscriptions::discord_user_id.eq(discord_user_id)),
+    )
+        .set(subscriptions::language.eq(language))
+        .execute(&mut conn)
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Failed to update subscription language: {}", e);
+            Err(anyhow::anyhow!("Database update error: {}", e))
+        }
+    }
+}
+
+pub fn unsubscribe(user_id: i32, discord_user_id: String) -> Result<Subscription> {
+    let mut conn = match get_connection() {
+        Some(c) => c,
+        None => {
+            return Err(anyhow::anyhow!("Failed to get DB connection"));
+        }
+    };
+
+    match diesel::delete(
+        subscriptions::table
+            .filter(subscriptions::followed_user_id.eq(user_id))
+            .filter(subscriptions::discord_user_id.eq(discord_user_id)),
+    )
+        .get_result::<Subscription>(&mut conn)
+    {
+        Ok(deleted) => Ok(deleted),
+        Err(e) => {
+            tracing::error!("Failed to unsubscribe from user: {}", e);
+            Err(anyhow::anyhow!("Database delete error: {}", e))
+        }
+    }
+}
+
+fn get_connection() -> Option<DbConnection> {
+    match crate::provider::db::DbProvider::global().get_connection() {
+        Ok(c) => Some(c),
+        Err(e) => {
+            tracing::error!("Failed to get DB connection: {}", e);
+            None
+        }
+    }
+}
@@ -1,5 +1,36 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    channel_settings (channel_id) {
+        #[max_length = 255]
+        channel_id -> Varchar,
+        #[max_length = 16]
+        delivery_mode -> Varchar,
+        digest_time -> Time,
+        digest_weekday -> Int4,
+        #[max_length = 64]
+        timezone -> Varchar,
+        last_digest_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    digest_queue (id) {
+        id -> Int4,
+        #[max_length = 255]
+        channel_id -> Varchar,
+        #[max_length = 255]
+        user_name -> Varchar,
+        #[max_length = 255]
+        gmaps_id -> Varchar,
+        #[max_length = 255]
+        place_name -> Varchar,
+        stars -> Int4,
+        link -> Nullable<Text>,
+        queued_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     following (id) {
         id -> Int4,
@@ -9,6 +40,16 @@ diesel::table! {
         original_text -> Bool,
         #[max_length = 20]
         webhook_id -> Varchar,
+        #[max_length = 20]
+        mention_role_id -> Nullable<Varchar>,
+        mention_user_ids -> Jsonb,
+        mention_here -> Bool,
+        thread_per_user -> Bool,
+        #[max_length = 20]
+        thread_id -> Nullable<Varchar>,
+        forum -> Bool,
+        #[max_length = 35]
+        language -> Nullable<Varchar>,
     }
 }
 
@@ -24,6 +65,49 @@ diesel::table! {
         found_at -> Timestamp,
         link_en -> Nullable<Text>,
         pictures -> Jsonb,
+        #[max_length = 35]
+        language -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    short_links (id) {
+        id -> Int4,
+        url -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    short_urls (shortener, original_url) {
+        shortener -> Text,
+        original_url -> Text,
+        short_url -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    sinks (id) {
+        id -> Int4,
+        followed_user_id -> Int4,
+        #[max_length = 16]
+        kind -> Varchar,
+        target -> Text,
+        secret -> Nullable<Text>,
+        config -> Jsonb,
+    }
+}
+
+diesel::table! {
+    subscriptions (id) {
+        id -> Int4,
+        followed_user_id -> Int4,
+        #[max_length = 20]
+        discord_user_id -> Varchar,
+        original_text -> Bool,
+        #[max_length = 35]
+        language -> Nullable<Varchar>,
     }
 }
 
@@ -39,5 +123,17 @@ diesel::table! {
 
 diesel::joinable!(following -> users (followed_user_id));
 diesel::joinable!(reviews -> users (user_id));
+diesel::joinable!(sinks -> users (followed_user_id));
+diesel::joinable!(subscriptions -> users (followed_user_id));
 
-diesel::allow_tables_to_appear_in_same_query!(following, reviews, users,);
+diesel::allow_tables_to_appear_in_same_query!(
+    channel_settings,
+    digest_queue,
+    following,
+    reviews,
+    short_links,
+    short_urls,
+    sinks,
+    subscriptions,
+    users,
+);
@@ -1,13 +1,28 @@
 use crate::models::ReviewWithUser;
-use poise::serenity_prelude::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter};
+use poise::serenity_prelude::{
+    ButtonStyle, CreateActionRow, CreateButton, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter,
+};
 
 use crate::crawler::pages::user::GMAPS_USER_URL;
 
-pub fn get_review_embed(review_with_user: &ReviewWithUser, original: bool) -> CreateEmbed {
+static REVIEW_BODY_TOGGLE_PREFIX: &str = "review_body";
+/// Discord groups at most four embed images that share the same URL into a single gallery.
+const GALLERY_IMAGE_LIMIT: usize = 4;
+
+/// Builds the review embed followed by the gallery embeds for its additional pictures.
+pub fn get_review_embeds(review_with_user: &ReviewWithUser, original: bool) -> Vec<CreateEmbed> {
     let review_body = select_review_body(review_with_user, original);
+    let user_url = GMAPS_USER_URL.replace("{}", review_with_user.user.gmaps_id.as_str());
+    // Gallery embeds are only merged with the main embed when all of them share this URL.
+    let gallery_url = review_with_user
+        .review
+        .link_en
+        .clone()
+        .unwrap_or_else(|| user_url.clone());
 
     let mut embed = CreateEmbed::default()
         .title(review_with_user.review.place_name.clone())
+        .url(gallery_url.clone())
         .field(
             "Stars",
             crate::config::get_config()
@@ -15,24 +30,74 @@ pub fn get_review_embed(review_with_user: &ReviewWithUser, original: bool) -> Cr
                 .repeat(review_with_user.review.stars.try_into().unwrap()),
             false,
         )
-        .author(CreateEmbedAuthor::new(review_with_user.user.name.clone()).url(GMAPS_USER_URL.replace("{}", review_with_user.user.gmaps_id.as_str())))
+        .author(CreateEmbedAuthor::new(review_with_user.user.name.clone()).url(user_url))
         .timestamp(review_with_user.review.found_at.and_utc())
         .footer(CreateEmbedFooter::new(format!(
             "Due to caching, this review may be up to {} hours old.",
-            crate::config::get_config().review_age_limit_hours
+            crate::config::get_config().scheduler.review_age_limit_hours
         )));
 
-    if review_with_user.review.link_en.is_some() {
-        embed = embed.url(review_with_user.review.link_en.clone().unwrap());
+    let valid_pictures = collect_valid_pictures(&review_with_user.review.pictures);
+    let (gallery_pictures, overflow_pictures) = split_gallery_pictures(&valid_pictures);
+    embed = embed.description(build_review_description(review_body, overflow_pictures));
+
+    let mut gallery = gallery_pictures.iter();
+    if let Some(first_picture) = gallery.next() {
+        embed = embed.image(*first_picture);
     }
 
-    let valid_pictures = collect_valid_pictures(&review_with_user.review.pictures);
-    embed = embed.description(build_review_description(review_body, &valid_pictures));
+    let mut embeds = vec![embed];
+    embeds.extend(gallery.map(|picture| CreateEmbed::default().url(gallery_url.clone()).image(*picture)));
+    embeds
+}
 
-    embed
+/// Builds the "Show original" / "Show translation" toggle for a review embed.
+///
+/// Returns no components when the review has no separate original text to switch to.
+pub fn get_review_components(review_with_user: &ReviewWithUser, original: bool) -> Vec<CreateActionRow> {
+    let has_distinct_original = review_with_user
+        .review
+        .original_text
+        .as_deref()
+        .is_some_and(|original_text| original_text != review_with_user.review.text);
+    if !has_distinct_original {
+        return vec![];
+    }
+
+    let label = if original { "Show translation" } else { "Show original" };
+    let button = CreateButton::new(review_body_toggle_id(review_with_user.review.id, !original))
+        .label(label)
+        .style(ButtonStyle::Secondary);
+
+    vec![CreateActionRow::Buttons(vec![button])]
 }
 
-fn select_review_body<'a>(review_with_user: &'a ReviewWithUser, original: bool) -> &'a str {
+/// Parses a toggle button custom ID into the review ID and whether the original text should be shown.
+pub fn parse_review_body_toggle_id(custom_id: &str) -> Option<(i32, bool)> {
+    let mut parts = custom_id.split(':');
+    if parts.next()? != REVIEW_BODY_TOGGLE_PREFIX {
+        return None;
+    }
+
+    let review_id = parts.next()?.parse().ok()?;
+    let original = match parts.next()? {
+        "original" => true,
+        "translation" => false,
+        _ => return None,
+    };
+
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((review_id, original))
+}
+
+fn review_body_toggle_id(review_id: i32, original: bool) -> String {
+    let body = if original { "original" } else { "translation" };
+    format!("{REVIEW_BODY_TOGGLE_PREFIX}:{review_id}:{body}")
+}
+
+pub(crate) fn select_review_body(review_with_user: &ReviewWithUser, original: bool) -> &str {
     if original {
         review_with_user
             .review
@@ -44,7 +109,7 @@ fn select_review_body<'a>(review_with_user: &'a ReviewWithUser, original: bool)
     }
 }
 
-fn collect_valid_pictures(pictures: &serde_json::Value) -> Vec<&str> {
+pub(crate) fn collect_valid_pictures(pictures: &serde_json::Value) -> Vec<&str> {
     pictures
         .as_array()
         .map(|pictures_arr| {
@@ -57,21 +122,29 @@ fn collect_valid_pictures(pictures: &serde_json::Value) -> Vec<&str> {
         .unwrap_or_default()
 }
 
-fn build_review_description(review_body: &str, valid_pictures: &[&str]) -> String {
-    if valid_pictures.is_empty() {
+fn split_gallery_pictures<'a>(pictures: &'a [&'a str]) -> (&'a [&'a str], &'a [&'a str]) {
+    pictures.split_at(pictures.len().min(GALLERY_IMAGE_LIMIT))
+}
+
+/// Appends links for the pictures that did not fit into the embed gallery.
+fn build_review_description(review_body: &str, overflow_pictures: &[&str]) -> String {
+    if overflow_pictures.is_empty() {
         return review_body.to_string();
     }
 
     let mut description = format!("{review_body}\n\n\n");
-    for (idx, pic) in valid_pictures.iter().enumerate() {
-        description.push_str(format!("[Picture {}]({})\n", idx + 1, pic).as_str());
+    for (idx, pic) in overflow_pictures.iter().enumerate() {
+        description.push_str(format!("[Picture {}]({})\n", GALLERY_IMAGE_LIMIT + idx + 1, pic).as_str());
     }
     description
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{build_review_description, collect_valid_pictures, select_review_body};
+    use super::{
+        build_review_description, collect_valid_pictures, get_review_components,
+        parse_review_body_toggle_id, review_body_toggle_id, select_review_body, split_gallery_pictures,
+    };
     use crate::models::{Review, ReviewWithUser, User};
     use chrono::Utc;
     use serde_json::json;
@@ -93,6 +166,7 @@ mod tests {
                 found_at: Utc::now().naive_utc(),
                 link_en: Some("https://example.com/review".to_string()),
                 pictures: json!([]),
+                language: None,
             },
         }
     }
@@ -110,6 +184,38 @@ mod tests {
         assert_eq!(select_review_body(&review_with_user, false), "Translated text");
     }
 
+    #[test]
+    fn get_review_components_is_empty_without_original_text() {
+        let review_with_user = sample_review_with_user(None);
+        assert!(get_review_components(&review_with_user, true).is_empty());
+    }
+
+    #[test]
+    fn get_review_components_is_empty_when_original_matches_text() {
+        let review_with_user = sample_review_with_user(Some("Translated text"));
+        assert!(get_review_components(&review_with_user, false).is_empty());
+    }
+
+    #[test]
+    fn get_review_components_adds_toggle_button() {
+        let review_with_user = sample_review_with_user(Some("Original text"));
+        assert_eq!(get_review_components(&review_with_user, true).len(), 1);
+    }
+
+    #[test]
+    fn review_body_toggle_id_round_trips() {
+        assert_eq!(parse_review_body_toggle_id(&review_body_toggle_id(7, true)), Some((7, true)));
+        assert_eq!(parse_review_body_toggle_id(&review_body_toggle_id(7, false)), Some((7, false)));
+    }
+
+    #[test]
+    fn parse_review_body_toggle_id_rejects_foreign_ids() {
+        assert_eq!(parse_review_body_toggle_id("something_else:7:original"), None);
+        assert_eq!(parse_review_body_toggle_id("review_body:abc:original"), None);
+        assert_eq!(parse_review_body_toggle_id("review_body:7:unknown"), None);
+        assert_eq!(parse_review_body_toggle_id("review_body:7:original:extra"), None);
+    }
+
     #[test]
     fn collect_valid_pictures_keeps_only_non_empty_strings() {
         let pictures = json!(["https://img/1", "   ", null, 1, "https://img/2"]);
@@ -122,11 +228,33 @@ mod tests {
     }
 
     #[test]
-    fn build_review_description_with_pictures_adds_links() {
-        let description = build_review_description("Body", &["https://img/1", "https://img/2"]);
+    fn build_review_description_with_overflow_pictures_adds_links() {
+        let description = build_review_description("Body", &["https://img/5", "https://img/6"]);
         assert_eq!(
             description,
-            "Body\n\n\n[Picture 1](https://img/1)\n[Picture 2](https://img/2)\n"
+            "Body\n\n\n[Picture 5](https://img/5)\n[Picture 6](https://img/6)\n"
         );
     }
+
+    #[test]
+    fn split_gallery_pictures_keeps_short_lists_in_gallery() {
+        let pictures = ["https://img/1", "https://img/2"];
+        let (gallery, overflow) = split_gallery_pictures(&pictures);
+        assert_eq!(gallery, ["https://img/1", "https://img/2"]);
+        assert!(overflow.is_empty());
+    }
+
+    #[test]
+    fn split_gallery_pictures_limits_gallery_to_four_images() {
+        let pictures = [
+            "https://img/1",
+            "https://img/2",
+            "https://img/3",
+            "https://img/4",
+            "https://img/5",
+        ];
+        let (gallery, overflow) = split_gallery_pictures(&pictures);
+        assert_eq!(gallery.len(), 4);
+        assert_eq!(overflow, ["https://img/5"]);
+    }
 }
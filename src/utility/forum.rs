@@ -0,0 +1,59 @@
+/// Discord rejects thread (forum post) names longer than this.
+const POST_TITLE_LIMIT: usize = 100;
+/// Discord rejects forum tag names longer than this.
+const TAG_NAME_LIMIT: usize = 20;
+/// A forum channel can hold at most this many tags.
+pub const FORUM_TAG_LIMIT: usize = 20;
+
+/// Title of the forum post created for a review, e.g. `Cafe ⭐⭐⭐⭐`.
+/// Long place names are shortened so the stars still fit.
+pub fn forum_post_title(place_name: &str, stars: i32, star_text: &str) -> String {
+    let stars = truncate(
+        star_text.repeat(stars.max(0).try_into().unwrap_or_default()).as_str(),
+        POST_TITLE_LIMIT,
+    );
+    let stars_len = stars.chars().count();
+    if stars_len == 0 {
+        return truncate(place_name.trim(), POST_TITLE_LIMIT);
+    }
+
+    let place_name = truncate(place_name.trim(), POST_TITLE_LIMIT.saturating_sub(stars_len + 1));
+    format!("{place_name} {stars}").trim().to_string()
+}
+
+/// Name of the forum tag applied to posts with the given star rating.
+pub fn star_tag_name(stars: i32) -> String {
+    let name = if stars == 1 {
+        "1 star".to_string()
+    } else {
+        format!("{stars} stars")
+    };
+    truncate(name.as_str(), TAG_NAME_LIMIT)
+}
+
+fn truncate(value: &str, limit: usize) -> String {
+    value.chars().take(limit).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{forum_post_title, star_tag_name};
+
+    #[test]
+    fn forum_post_title_appends_stars() {
+        assert_eq!(forum_post_title("Cafe", 3, "⭐"), "Cafe ⭐⭐⭐");
+    }
+
+    #[test]
+    fn forum_post_title_is_truncated() {
+        let title = forum_post_title("P".repeat(150).as_str(), 5, "⭐");
+        assert_eq!(title.chars().count(), 100);
+        assert!(title.ends_with(" ⭐⭐⭐⭐⭐"));
+    }
+
+    #[test]
+    fn star_tag_name_uses_singular_for_one_star() {
+        assert_eq!(star_tag_name(1), "1 star");
+        assert_eq!(star_tag_name(4), "4 stars");
+    }
+}
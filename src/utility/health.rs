@@ -0,0 +1,161 @@
+use crate::utility::metrics;
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+/// How long a review check may run past its next scheduled start before it counts as stale.
+const REVIEW_CHECK_GRACE: chrono::Duration = chrono::Duration::hours(1);
+
+static STARTED_AT: LazyLock<DateTime<Utc>> = LazyLock::new(Utc::now);
+static LAST_REVIEW_CHECK: Mutex<Option<DateTime<Utc>>> = Mutex::new(None);
+static RUNNING_REVIEW_CHECKS: AtomicUsize = AtomicUsize::new(0);
+/// Start of the oldest running review check as a Unix timestamp, or 0 when none runs.
+static RUNNING_REVIEW_CHECK_SINCE: AtomicI64 = AtomicI64::new(0);
+static GATEWAY_SHARDS: LazyLock<Mutex<HashMap<u32, bool>>> = LazyLock::new(Mutex::default);
+
+/// Remembers when the process started, so a missing first review check can be judged from there.
+pub fn init() {
+    LazyLock::force(&STARTED_AT);
+}
+
+/// Tracks the connection state of each gateway shard from the Discord events.
+pub fn record_gateway_event(event: &serenity::FullEvent) {
+    let (shard_id, connected) = match event {
+        serenity::FullEvent::Ready { data_about_bot } => {
+            (data_about_bot.shard.map(|shard| shard.id.0).unwrap_or_default(), true)
+        }
+        serenity::FullEvent::ShardStageUpdate { event } => {
+            (event.shard_id.0, event.new == serenity::ConnectionStage::Connected)
+        }
+        _ => return,
+    };
+
+    if let Ok(mut shards) = GATEWAY_SHARDS.lock() {
+        shards.insert(shard_id, connected);
+    }
+}
+
+/// Whether at least one shard connected and none is currently disconnected.
+pub fn is_gateway_connected() -> bool {
+    GATEWAY_SHARDS
+        .lock()
+        .map(|shards| !shards.is_empty() && shards.values().all(|connected| *connected))
+        .unwrap_or(false)
+}
+
+/// Marks a review check as running until the returned guard is dropped.
+pub fn record_review_check_started() -> RunningReviewCheck {
+    RUNNING_REVIEW_CHECKS.fetch_add(1, Ordering::SeqCst);
+    let started_at = Utc::now().timestamp();
+    // An overlapping check keeps the start of the older one, which is the one that may be stuck.
+    let oldest = RUNNING_REVIEW_CHECK_SINCE
+        .compare_exchange(0, started_at, Ordering::SeqCst, Ordering::SeqCst)
+        .is_ok();
+    RunningReviewCheck { started_at: oldest.then_some(started_at) }
+}
+
+/// Keeps a review check counted as running, also when it ends early.
+pub struct RunningReviewCheck {
+    started_at: Option<i64>,
+}
+
+impl Drop for RunningReviewCheck {
+    fn drop(&mut self) {
+        if let Some(started_at) = self.started_at {
+            let _ = RUNNING_REVIEW_CHECK_SINCE.compare_exchange(started_at, 0, Ordering::SeqCst, Ordering::SeqCst);
+        }
+        RUNNING_REVIEW_CHECKS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub fn is_review_check_running() -> bool {
+    RUNNING_REVIEW_CHECKS.load(Ordering::SeqCst) > 0
+}
+
+pub fn record_review_check_completed() {
+    let now = Utc::now();
+    if let Ok(mut last) = LAST_REVIEW_CHECK.lock() {
+        *last = Some(now);
+    }
+    metrics::record_review_check_completed(now);
+}
+
+pub fn last_review_check() -> Option<DateTime<Utc>> {
+    LAST_REVIEW_CHECK.lock().ok().and_then(|last| *last)
+}
+
+fn running_review_check_since() -> Option<DateTime<Utc>> {
+    match RUNNING_REVIEW_CHECK_SINCE.load(Ordering::SeqCst) {
+        0 => None,
+        started_at => DateTime::from_timestamp(started_at, 0),
+    }
+}
+
+/// Whether the review checks fell behind the configured schedule.
+pub fn is_review_check_stale(schedule: &str, now: DateTime<Utc>) -> bool {
+    let reference = last_review_check().unwrap_or(*STARTED_AT);
+    is_overdue(schedule, reference, running_review_check_since(), now)
+}
+
+/// A running check is judged from its start, so one stuck on the browser turns stale like a missed one.
+fn is_overdue(
+    schedule: &str,
+    reference: DateTime<Utc>,
+    running_since: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    review_check_deadline(schedule, running_since.unwrap_or(reference)).is_some_and(|deadline| now > deadline)
+}
+
+/// The latest time the check after `reference` should have finished, or `None` for an invalid schedule.
+fn review_check_deadline(schedule: &str, reference: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let cron = crate::config::parse_cron(schedule).ok()?;
+    let next = cron.find_next_occurrence(&reference, false).ok()?;
+    Some(next + REVIEW_CHECK_GRACE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_overdue, review_check_deadline};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn review_check_deadline_follows_schedule() {
+        let reference = Utc.with_ymd_and_hms(2026, 3, 1, 7, 30, 0).unwrap();
+
+        assert_eq!(
+            review_check_deadline("0 0 */6 * * *", reference),
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 13, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn review_check_deadline_is_none_for_invalid_schedule() {
+        assert_eq!(review_check_deadline("every now and then", Utc::now()), None);
+    }
+
+    #[test]
+    fn recently_started_review_check_is_not_overdue() {
+        let reference = Utc.with_ymd_and_hms(2026, 3, 1, 1, 30, 0).unwrap();
+        let started = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 15, 0, 0).unwrap();
+
+        assert!(is_overdue("0 0 */6 * * *", reference, None, now));
+        assert!(!is_overdue("0 0 */6 * * *", reference, Some(started), now));
+    }
+
+    #[test]
+    fn review_check_running_too_long_is_overdue() {
+        let reference = Utc.with_ymd_and_hms(2026, 3, 1, 5, 0, 0).unwrap();
+        let started = Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap();
+
+        let within_grace = Utc.with_ymd_and_hms(2026, 3, 1, 13, 0, 0).unwrap();
+        let past_grace = Utc.with_ymd_and_hms(2026, 3, 1, 13, 1, 0).unwrap();
+
+        // Started at 06:00, the check should be done by the next run at 12:00 plus the grace hour.
+        assert!(!is_overdue("0 0 */6 * * *", reference, Some(started), within_grace));
+        assert!(is_overdue("0 0 */6 * * *", reference, Some(started), past_grace));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::models::{Review, ReviewWithUser};
+use reqwest::Url;
+
+/// Query parameter Google Maps picks its display language from.
+static DISPLAY_LANGUAGE_PARAM: &str = "hl";
+/// Value of the `language` option that removes the display language.
+static NO_DISPLAY_LANGUAGE: &str = "none";
+
+/// Checks a language code like `en`, `de` or `pt-BR` and returns it with the language in lowercase.
+pub fn parse_language_code(code: &str) -> Result<String, String> {
+    let invalid = || format!("'{}' is not a language code like en, de or pt-BR", code);
+    let mut subtags = code.trim().split('-');
+    let language = subtags.next().unwrap_or_default();
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(invalid());
+    }
+
+    let mut normalized = language.to_ascii_lowercase();
+    for subtag in subtags {
+        if !(2..=8).contains(&subtag.len()) || !subtag.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid());
+        }
+        normalized.push('-');
+        normalized.push_str(subtag);
+    }
+    Ok(normalized)
+}
+
+/// Parses the `language` option of `/follow` and `/subscribe`: a language code sets the display language, `none` removes it.
+pub fn parse_display_language(option: &str) -> Result<Option<String>, String> {
+    if option.trim().eq_ignore_ascii_case(NO_DISPLAY_LANGUAGE) {
+        return Ok(None);
+    }
+    parse_language_code(option).map(Some)
+}
+
+/// Whether both codes name the same language, regardless of region or script.
+pub fn same_language(a: &str, b: &str) -> bool {
+    let language = |code: &str| code.split('-').next().unwrap_or_default().to_ascii_lowercase();
+    language(a) == language(b)
+}
+
+/// Points a Google Maps URL at the given display language; other URLs, such as short links, are kept.
+pub fn with_display_language(url: &str, language: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    if !parsed.host_str().is_some_and(|host| host.contains("google.")) {
+        return url.to_string();
+    }
+
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(name, _)| name != DISPLAY_LANGUAGE_PARAM)
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    parsed
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(DISPLAY_LANGUAGE_PARAM, language);
+    parsed.to_string()
+}
+
+/// Reviews written in the display language are shown as written, as they need no translation.
+pub fn shows_original(original_text: bool, display_language: Option<&str>, review: &Review) -> bool {
+    original_text
+        || display_language
+            .zip(review.language.as_deref())
+            .is_some_and(|(display, written)| same_language(display, written))
+}
+
+/// The review with its link opening Google Maps in the display language.
+pub fn localized_review(review_with_user: &ReviewWithUser, display_language: Option<&str>) -> ReviewWithUser {
+    let mut localized = review_with_user.clone();
+    if let Some(language) = display_language {
+        localized.review.link_en = localized
+            .review
+            .link_en
+            .map(|link| with_display_language(link.as_str(), language));
+    }
+    localized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_display_language, parse_language_code, same_language, shows_original, with_display_language};
+    use crate::models::Review;
+    use chrono::Utc;
+    use serde_json::json;
+
+    #[test]
+    fn parse_language_code_normalizes_language() {
+        assert_eq!(parse_language_code(" DE ").unwrap(), "de");
+        assert_eq!(parse_language_code("pt-BR").unwrap(), "pt-BR");
+        assert!(parse_language_code("english").is_err());
+        assert!(parse_language_code("en_US").is_err());
+        assert!(parse_language_code("").is_err());
+    }
+
+    #[test]
+    fn parse_display_language_accepts_none() {
+        assert_eq!(parse_display_language("None"), Ok(None));
+        assert_eq!(parse_display_language("de"), Ok(Some("de".to_string())));
+        assert!(parse_display_language("nothing").is_err());
+    }
+
+    #[test]
+    fn same_language_ignores_region() {
+        assert!(same_language("pt-BR", "pt"));
+        assert!(same_language("EN", "en-GB"));
+        assert!(!same_language("en", "de"));
+    }
+
+    #[test]
+    fn shows_original_when_written_in_display_language() {
+        let review = Review {
+            id: 1,
+            place_name: "Cafe".to_string(),
+            text: "Great coffee".to_string(),
+            original_text: Some("Geweldige koffie".to_string()),
+            stars: 5,
+            user_id: 1,
+            found_at: Utc::now().naive_utc(),
+            link_en: None,
+            pictures: json!([]),
+            language: Some("nl".to_string()),
+        };
+
+        assert!(shows_original(false, Some("nl-BE"), &review));
+        assert!(!shows_original(false, Some("de"), &review));
+        assert!(!shows_original(false, None, &review));
+        assert!(shows_original(true, None, &review));
+    }
+
+    #[test]
+    fn with_display_language_replaces_google_language_only() {
+        assert_eq!(
+            with_display_language("https://www.google.com/maps/place/Cafe/@48.8,2.3,17z?entry=ttu&hl=en", "de"),
+            "https://www.google.com/maps/place/Cafe/@48.8,2.3,17z?entry=ttu&hl=de"
+        );
+        assert_eq!(with_display_language("https://s.example.com/abc", "de"), "https://s.example.com/abc");
+    }
+}
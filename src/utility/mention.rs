@@ -0,0 +1,217 @@
+use crate::models::Following;
+use poise::serenity_prelude::{CreateAllowedMentions, RoleId, UserId};
+
+/// Who should be pinged when a followed user posts a new review.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mentions {
+    pub role_id: Option<String>,
+    pub user_ids: Vec<String>,
+    pub here: bool,
+}
+
+impl Mentions {
+    pub fn from_following(following: &Following) -> Self {
+        Mentions {
+            role_id: following.mention_role_id.clone(),
+            user_ids: following
+                .mention_user_ids
+                .as_array()
+                .map(|ids| {
+                    ids.iter()
+                        .filter_map(|id| id.as_str())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            here: following.mention_here,
+        }
+    }
+
+    /// Message content that pings the configured targets, if any.
+    pub fn content(&self) -> Option<String> {
+        combined_content(std::slice::from_ref(self))
+    }
+
+    /// Restricts pings to exactly the configured targets.
+    pub fn allowed_mentions(&self) -> CreateAllowedMentions {
+        combined_allowed_mentions(std::slice::from_ref(self))
+    }
+}
+
+/// Message content that pings every target of the given mentions once, e.g. for a digest of several follows.
+pub fn combined_content(mentions: &[Mentions]) -> Option<String> {
+    let here = mentions.iter().any(|m| m.here);
+    let role_ids = unique(mentions.iter().filter_map(|m| m.role_id.as_ref()));
+    let user_ids = unique(mentions.iter().flat_map(|m| m.user_ids.iter()));
+    if !here && role_ids.is_empty() && user_ids.is_empty() {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+    if here {
+        parts.push("@here".to_string());
+    }
+    parts.extend(role_ids.iter().map(|role_id| format!("<@&{role_id}>")));
+    parts.extend(user_ids.iter().map(|user_id| format!("<@{user_id}>")));
+    Some(parts.join(" "))
+}
+
+/// Restricts pings to exactly the targets of the given mentions.
+pub fn combined_allowed_mentions(mentions: &[Mentions]) -> CreateAllowedMentions {
+    let roles = unique(mentions.iter().filter_map(|m| m.role_id.as_ref()))
+        .into_iter()
+        .filter_map(|id| id.parse::<u64>().ok())
+        .map(RoleId::new);
+    let users = unique(mentions.iter().flat_map(|m| m.user_ids.iter()))
+        .into_iter()
+        .filter_map(|id| id.parse::<u64>().ok())
+        .map(UserId::new);
+
+    CreateAllowedMentions::new()
+        .everyone(mentions.iter().any(|m| m.here))
+        .roles(roles)
+        .users(users)
+}
+
+fn unique<'a>(ids: impl Iterator<Item = &'a String>) -> Vec<&'a String> {
+    let mut unique: Vec<&String> = Vec::new();
+    for id in ids {
+        if !unique.contains(&id) {
+            unique.push(id);
+        }
+    }
+    unique
+}
+
+/// Changes to the mentions of a follow; targets left unset keep their stored value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MentionUpdate {
+    pub role_id: Option<String>,
+    pub user_ids: Option<Vec<String>>,
+    pub here: Option<bool>,
+    /// Removes all stored targets before the given ones are applied.
+    pub clear: bool,
+}
+
+impl MentionUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.role_id.is_none() && self.user_ids.is_none() && self.here.is_none() && !self.clear
+    }
+
+    pub fn apply(&self, current: &Mentions) -> Mentions {
+        let base = if self.clear { Mentions::default() } else { current.clone() };
+        Mentions {
+            role_id: self.role_id.clone().or(base.role_id),
+            user_ids: self.user_ids.clone().unwrap_or(base.user_ids),
+            here: self.here.unwrap_or(base.here),
+        }
+    }
+}
+
+/// Extracts user IDs from a list of `<@id>` mentions or raw IDs.
+pub fn parse_user_mentions(input: &str) -> Vec<String> {
+    let mut user_ids = Vec::new();
+    for token in input.split(|c: char| c.is_whitespace() || c == ',') {
+        let id = token
+            .trim_start_matches("<@")
+            .trim_start_matches('!')
+            .trim_end_matches('>');
+        if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) && !user_ids.iter().any(|u| u == id) {
+            user_ids.push(id.to_string());
+        }
+    }
+    user_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{combined_content, parse_user_mentions, MentionUpdate, Mentions};
+
+    fn stored_mentions() -> Mentions {
+        Mentions {
+            role_id: Some("10".to_string()),
+            user_ids: vec!["20".to_string()],
+            here: false,
+        }
+    }
+
+    #[test]
+    fn content_is_none_without_targets() {
+        assert_eq!(Mentions::default().content(), None);
+    }
+
+    #[test]
+    fn content_lists_all_targets() {
+        let mentions = Mentions {
+            role_id: Some("10".to_string()),
+            user_ids: vec!["20".to_string(), "30".to_string()],
+            here: true,
+        };
+        assert_eq!(mentions.content().as_deref(), Some("@here <@&10> <@20> <@30>"));
+    }
+
+    #[test]
+    fn combined_content_pings_each_target_once() {
+        let other = Mentions {
+            role_id: Some("11".to_string()),
+            user_ids: vec!["20".to_string(), "21".to_string()],
+            here: false,
+        };
+
+        assert_eq!(
+            combined_content(&[stored_mentions(), other, stored_mentions()]).as_deref(),
+            Some("<@&10> <@&11> <@20> <@21>")
+        );
+        assert_eq!(combined_content(&[Mentions::default()]), None);
+    }
+
+    #[test]
+    fn parse_user_mentions_accepts_mentions_and_raw_ids() {
+        assert_eq!(
+            parse_user_mentions("<@123> <@!456>, 789"),
+            vec!["123".to_string(), "456".to_string(), "789".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_user_mentions_skips_invalid_and_duplicate_entries() {
+        assert_eq!(
+            parse_user_mentions("<@&42> alice <@123> 123"),
+            vec!["123".to_string()]
+        );
+    }
+
+    #[test]
+    fn apply_keeps_targets_that_are_not_updated() {
+        let update = MentionUpdate {
+            here: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            update.apply(&stored_mentions()),
+            Mentions {
+                here: true,
+                ..stored_mentions()
+            }
+        );
+    }
+
+    #[test]
+    fn apply_clears_stored_targets_first() {
+        let update = MentionUpdate {
+            user_ids: Some(vec!["30".to_string()]),
+            clear: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            update.apply(&stored_mentions()),
+            Mentions {
+                role_id: None,
+                user_ids: vec!["30".to_string()],
+                here: false,
+            }
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Crawls usually take a few seconds per user; anything past the page timeout ends up in the top buckets.
+const CRAWL_DURATION_BUCKETS: [f64; 9] = [0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0];
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// How a crawl of a user's latest review ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrawlOutcome {
+    New,
+    Unchanged,
+    Failed,
+}
+
+impl CrawlOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CrawlOutcome::New => "new",
+            CrawlOutcome::Unchanged => "unchanged",
+            CrawlOutcome::Failed => "failed",
+        }
+    }
+}
+
+/// How a call to shorten a URL was answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortenerResult {
+    Cached,
+    Shortened,
+    Failed,
+    /// The circuit breaker was open, so the shortener wasn't called.
+    Skipped,
+}
+
+impl ShortenerResult {
+    fn as_str(self) -> &'static str {
+        match self {
+            ShortenerResult::Cached => "cached",
+            ShortenerResult::Shortened => "shortened",
+            ShortenerResult::Failed => "failed",
+            ShortenerResult::Skipped => "skipped",
+        }
+    }
+}
+
+/// Numbers of follows, subscriptions and sinks, read from the database when the metrics are scraped.
+pub struct FollowCounts {
+    pub followed_users: i64,
+    pub channel_follows: i64,
+    pub subscriptions: i64,
+    pub sinks: i64,
+}
+
+struct Metrics {
+    registry: Registry,
+    crawls: IntCounterVec,
+    crawl_duration: Histogram,
+    notifications: IntCounterVec,
+    shortener_calls: IntCounterVec,
+    review_check_completed: IntGauge,
+    db_pool_connections: IntGaugeVec,
+    followed_users: IntGauge,
+    follows: IntGaugeVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let metrics = Metrics {
+            registry: Registry::new_custom(Some("gmaps".to_string()), None).expect("valid metrics registry"),
+            crawls: IntCounterVec::new(
+                Opts::new("crawls_total", "Crawls of a user's latest review by outcome"),
+                &["outcome"],
+            )
+                .expect("valid crawls metric"),
+            crawl_duration: Histogram::with_opts(
+                HistogramOpts::new("crawl_duration_seconds", "Time spent crawling a user's latest review")
+                    .buckets(CRAWL_DURATION_BUCKETS.to_vec()),
+            )
+                .expect("valid crawl duration metric"),
+            notifications: IntCounterVec::new(
+                Opts::new("notifications_total", "Review deliveries by target kind and result"),
+                &["kind", "result"],
+            )
+                .expect("valid notifications metric"),
+            shortener_calls: IntCounterVec::new(
+                Opts::new("shortener_calls_total", "URL shortener lookups by result"),
+                &["result"],
+            )
+                .expect("valid shortener metric"),
+            review_check_completed: IntGauge::new(
+                "review_check_last_completed_timestamp_seconds",
+                "Unix time the last review check finished",
+            )
+                .expect("valid review check metric"),
+            db_pool_connections: IntGaugeVec::new(
+                Opts::new("db_pool_connections", "Database pool connections by state"),
+                &["state"],
+            )
+                .expect("valid db pool metric"),
+            followed_users: IntGauge::new("followed_users", "Google Maps users followed in at least one channel")
+                .expect("valid followed users metric"),
+            follows: IntGaugeVec::new(
+                Opts::new("follows", "Registered follows, subscriptions and sinks"),
+                &["kind"],
+            )
+                .expect("valid follows metric"),
+        };
+
+        let collectors: [Box<dyn prometheus::core::Collector>; 8] = [
+            Box::new(metrics.crawls.clone()),
+            Box::new(metrics.crawl_duration.clone()),
+            Box::new(metrics.notifications.clone()),
+            Box::new(metrics.shortener_calls.clone()),
+            Box::new(metrics.review_check_completed.clone()),
+            Box::new(metrics.db_pool_connections.clone()),
+            Box::new(metrics.followed_users.clone()),
+            Box::new(metrics.follows.clone()),
+        ];
+        for collector in collectors {
+            metrics.registry.register(collector).expect("metrics are registered once");
+        }
+        metrics
+    }
+}
+
+pub fn record_crawl(outcome: CrawlOutcome) {
+    METRICS.crawls.with_label_values(&[outcome.as_str()]).inc();
+}
+
+pub fn record_crawl_duration(duration: Duration) {
+    METRICS.crawl_duration.observe(duration.as_secs_f64());
+}
+
+/// Counts a delivery to a notifier kind, e.g. `discord_channel` or a sink kind.
+pub fn record_notification(kind: &str, delivered: bool) {
+    let result = if delivered { "sent" } else { "failed" };
+    METRICS.notifications.with_label_values(&[kind, result]).inc();
+}
+
+pub fn record_shortener_call(result: ShortenerResult) {
+    METRICS.shortener_calls.with_label_values(&[result.as_str()]).inc();
+}
+
+pub fn record_review_check_completed(finished_at: chrono::DateTime<chrono::Utc>) {
+    METRICS.review_check_completed.set(finished_at.timestamp());
+}
+
+pub fn set_db_pool_connections(idle: u32, in_use: u32) {
+    METRICS.db_pool_connections.with_label_values(&["idle"]).set(idle.into());
+    METRICS.db_pool_connections.with_label_values(&["in_use"]).set(in_use.into());
+}
+
+pub fn set_follow_counts(counts: &FollowCounts) {
+    METRICS.followed_users.set(counts.followed_users);
+    METRICS.follows.with_label_values(&["channel"]).set(counts.channel_follows);
+    METRICS.follows.with_label_values(&["subscription"]).set(counts.subscriptions);
+    METRICS.follows.with_label_values(&["sink"]).set(counts.sinks);
+}
+
+/// Renders every metric in the Prometheus text exposition format.
+pub fn render() -> String {
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&METRICS.registry.gather(), &mut buffer) {
+        tracing::error!("Failed to encode metrics: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        record_crawl, record_crawl_duration, record_notification, record_shortener_call, render, CrawlOutcome,
+        ShortenerResult,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn render_includes_recorded_metrics() {
+        record_crawl(CrawlOutcome::Unchanged);
+        record_crawl_duration(Duration::from_secs(3));
+        record_notification("slack", false);
+        record_shortener_call(ShortenerResult::Cached);
+
+        let rendered = render();
+        assert!(rendered.contains("gmaps_crawls_total{outcome=\"unchanged\"}"));
+        assert!(rendered.contains("gmaps_crawl_duration_seconds_bucket{le=\"5\"}"));
+        assert!(rendered.contains("gmaps_notifications_total{kind=\"slack\",result=\"failed\"}"));
+        assert!(rendered.contains("gmaps_shortener_calls_total{result=\"cached\"}"));
+    }
+}
@@ -1,2 +1,8 @@
 pub mod embed;
+pub mod forum;
+pub mod health;
+pub mod language;
+pub mod mention;
+pub mod metrics;
 pub mod shorten;
+pub mod telemetry;
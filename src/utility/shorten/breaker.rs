@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Stops calling the shortener after consecutive failures until the next fetch cycle resets it.
+pub struct CircuitBreaker {
+    failures: AtomicU32,
+    threshold: u32,
+}
+
+impl CircuitBreaker {
+    pub const fn new(threshold: u32) -> Self {
+        CircuitBreaker {
+            failures: AtomicU32::new(0),
+            threshold,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.failures.load(Ordering::Relaxed) >= self.threshold
+    }
+
+    pub fn record_success(&self) {
+        self.failures.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns true when this failure opened the breaker.
+    pub fn record_failure(&self) -> bool {
+        self.failures.fetch_add(1, Ordering::Relaxed) + 1 == self.threshold
+    }
+
+    pub fn reset(&self) {
+        self.failures.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CircuitBreaker;
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new(3);
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        assert!(!breaker.is_open());
+        assert!(breaker.record_failure());
+        assert!(breaker.is_open());
+        assert!(!breaker.record_failure());
+    }
+
+    #[test]
+    fn success_clears_failures() {
+        let breaker = CircuitBreaker::new(2);
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        assert!(!breaker.is_open());
+    }
+
+    #[test]
+    fn reset_closes_breaker() {
+        let breaker = CircuitBreaker::new(1);
+        breaker.record_failure();
+        assert!(breaker.is_open());
+        breaker.reset();
+        assert!(!breaker.is_open());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::provider;
+use crate::utility::shorten::Shortener;
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Url;
+
+static CODE_ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// Path prefix under which the HTTP server redirects short codes.
+pub static SHORT_LINK_PATH: &str = "/s/";
+
+/// Stores URLs in the database and links to the bot's own redirect endpoint.
+pub struct BuiltinShortener {
+    public_url: String,
+}
+
+impl BuiltinShortener {
+    pub fn new(public_url: String) -> Self {
+        BuiltinShortener { public_url }
+    }
+}
+
+#[async_trait]
+impl Shortener for BuiltinShortener {
+    async fn shorten(&self, url: &Url) -> Result<String> {
+        let long_url = url.to_string();
+        let id = tokio::task::spawn_blocking(move || provider::short_link::get_or_create_short_link(long_url.as_str())).await??;
+        Ok(format!(
+            "{}{}{}",
+            self.public_url.trim_end_matches('/'),
+            SHORT_LINK_PATH,
+            encode_code(id)
+        ))
+    }
+}
+
+/// Encodes a short link id as a base62 code.
+pub fn encode_code(id: i32) -> String {
+    let mut id = u32::try_from(id).unwrap_or_default();
+    let mut code = Vec::new();
+    loop {
+        code.push(CODE_ALPHABET[(id % 62) as usize]);
+        id /= 62;
+        if id == 0 {
+            break;
+        }
+    }
+    code.reverse();
+    String::from_utf8(code).unwrap_or_default()
+}
+
+/// Decodes a base62 code back into a short link id.
+pub fn decode_code(code: &str) -> Option<i32> {
+    if code.is_empty() {
+        return None;
+    }
+    code.bytes().try_fold(0i32, |id, byte| {
+        let digit = CODE_ALPHABET.iter().position(|&c| c == byte)?;
+        id.checked_mul(62)?.checked_add(digit as i32)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_code, encode_code};
+
+    #[test]
+    fn codes_round_trip() {
+        for id in [0, 1, 61, 62, 3843, 1_000_000, i32::MAX] {
+            assert_eq!(decode_code(encode_code(id).as_str()), Some(id));
+        }
+        assert_eq!(encode_code(62), "10");
+        assert_eq!(encode_code(61), "Z");
+    }
+
+    #[test]
+    fn decode_code_rejects_invalid_codes() {
+        assert_eq!(decode_code(""), None);
+        assert_eq!(decode_code("ab-c"), None);
+        assert_eq!(decode_code("zzzzzzzzzz"), None);
+    }
+}
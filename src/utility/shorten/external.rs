@@ -0,0 +1,148 @@
+use crate::utility::shorten::Shortener;
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, Url};
+use serde_json::Value;
+use std::time::Duration;
+
+/// Posts URLs to an HTTP shortening service and reads the short URL from its JSON response.
+pub struct ExternalShortener {
+    client: Client,
+    url: String,
+    request_template: Option<String>,
+    response_pointer: String,
+    result_format: String,
+}
+
+impl ExternalShortener {
+    pub fn new(url: String, request_template: Option<String>, response_pointer: String, result_format: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("failed to build shortener HTTP client");
+        ExternalShortener {
+            client,
+            url,
+            request_template,
+            response_pointer,
+            result_format,
+        }
+    }
+}
+
+#[async_trait]
+impl Shortener for ExternalShortener {
+    async fn shorten(&self, url: &Url) -> Result<String> {
+        let request = match &self.request_template {
+            Some(template) => self
+                .client
+                .post(self.url.as_str())
+                .header(CONTENT_TYPE, "application/json")
+                .body(render_request(template, url)),
+            None => self.client.post(self.url.as_str()).body(url.as_str().to_owned()),
+        };
+
+        let resp = match request.send().await {
+            Ok(resp) => resp,
+            Err(err) => {
+                tracing::error!("Failed to send request to URL shortener: {}", err);
+                return Err(anyhow::anyhow!("Failed to send request to URL shortener: {err}"));
+            }
+        };
+
+        if !resp.status().is_success() {
+            return Err(anyhow::anyhow!("Request failed: {}", resp.status()));
+        }
+
+        let resp_json: Value = match resp.json().await {
+            Ok(json) => json,
+            Err(err) => {
+                tracing::error!("Failed to parse shorten response json: {}", err);
+                return Err(anyhow::anyhow!("Failed to parse shorten response json: {err}"));
+            }
+        };
+
+        match resp_json.pointer(self.response_pointer.as_str()).and_then(Value::as_str) {
+            Some(short) => Ok(self.result_format.replace("{}", short)),
+            None => Err(anyhow::anyhow!(
+                "Shorten response has no string at {}",
+                self.response_pointer
+            )),
+        }
+    }
+}
+
+/// Fills `{url}` in the template with the URL escaped as JSON string content.
+fn render_request(template: &str, url: &Url) -> String {
+    let quoted = Value::String(url.to_string()).to_string();
+    template.replace("{url}", &quoted[1..quoted.len() - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExternalShortener;
+    use crate::utility::shorten::Shortener;
+    use reqwest::Url;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, body_string, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn shorten_posts_plain_url() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/shorten"))
+            .and(body_string("https://maps.google.com/review?id=1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "url": "s.example.com/abc" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let shortener = ExternalShortener::new(
+            format!("{}/shorten", server.uri()),
+            None,
+            "/url".to_string(),
+            "https://{}".to_string(),
+        );
+        let url = Url::parse("https://maps.google.com/review?id=1").unwrap();
+
+        assert_eq!(shortener.shorten(&url).await.unwrap(), "https://s.example.com/abc");
+    }
+
+    #[tokio::test]
+    async fn shorten_maps_json_request_and_response() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(header("content-type", "application/json"))
+            .and(body_json(json!({ "long_url": "https://maps.google.com/review?q=a", "domain": "x.co" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": { "link": "https://x.co/1" } })))
+            .mount(&server)
+            .await;
+
+        let shortener = ExternalShortener::new(
+            format!("{}/api", server.uri()),
+            Some(r#"{"long_url": "{url}", "domain": "x.co"}"#.to_string()),
+            "/data/link".to_string(),
+            "{}".to_string(),
+        );
+        let url = Url::parse("https://maps.google.com/review?q=a").unwrap();
+
+        assert_eq!(shortener.shorten(&url).await.unwrap(), "https://x.co/1");
+    }
+
+    #[tokio::test]
+    async fn shorten_fails_without_value_at_pointer() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "error": "quota" })))
+            .mount(&server)
+            .await;
+
+        let shortener = ExternalShortener::new(server.uri(), None, "/url".to_string(), "{}".to_string());
+        let url = Url::parse("https://maps.google.com/").unwrap();
+
+        assert!(shortener.shorten(&url).await.is_err());
+    }
+}
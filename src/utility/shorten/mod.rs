@@ -0,0 +1,189 @@
+use crate::config::{get_config, Config, ShortenerConfig, ShortenerKind};
+use crate::provider;
+use crate::utility::metrics::{self, ShortenerResult};
+use anyhow::Result;
+use async_trait::async_trait;
+use breaker::CircuitBreaker;
+use reqwest::Url;
+use std::sync::{Arc, RwLock};
+
+pub mod breaker;
+pub mod builtin;
+pub mod external;
+
+/// Consecutive shortener failures after which shortening is skipped for the rest of the cycle.
+const FAILURE_THRESHOLD: u32 = 3;
+
+static SHORTENER: RwLock<Option<Arc<dyn Shortener>>> = RwLock::new(None);
+static BREAKER: CircuitBreaker = CircuitBreaker::new(FAILURE_THRESHOLD);
+
+/// Turns review links and pictures into shorter URLs before they are stored.
+#[async_trait]
+pub trait Shortener: Send + Sync {
+    async fn shorten(&self, url: &Url) -> Result<String>;
+}
+
+/// Keeps every URL as it is.
+pub struct DisabledShortener;
+
+#[async_trait]
+impl Shortener for DisabledShortener {
+    async fn shorten(&self, url: &Url) -> Result<String> {
+        Ok(url.to_string())
+    }
+}
+
+/// Builds the configured shortener, falling back to keeping URLs when its settings are incomplete.
+pub fn from_config(config: &ShortenerConfig, public_url: Option<&str>) -> Box<dyn Shortener> {
+    match config.kind {
+        ShortenerKind::Disabled => Box::new(DisabledShortener),
+        ShortenerKind::External => match &config.url {
+            Some(url) => Box::new(external::ExternalShortener::new(
+                url.clone(),
+                config.request_template.clone(),
+                config.response_pointer.clone(),
+                config.result_format.clone(),
+            )),
+            None => {
+                tracing::warn!("SHORTENER_URL is not set, links will not be shortened");
+                Box::new(DisabledShortener)
+            }
+        },
+        ShortenerKind::Builtin => match public_url {
+            Some(public_url) => Box::new(builtin::BuiltinShortener::new(public_url.to_string())),
+            None => {
+                tracing::warn!("PUBLIC_URL is not set, the built-in shortener can't build links");
+                Box::new(DisabledShortener)
+            }
+        },
+    }
+}
+
+/// Shortens a URL, reusing earlier results and skipping the shortener while it keeps failing.
+pub async fn shorten_url(url: &Url) -> Result<String> {
+    let config = get_config();
+    if config.shortener.kind == ShortenerKind::Disabled {
+        return Ok(url.to_string());
+    }
+
+    let cache_key = cache_key(&config.shortener);
+    if let Some(cache_key) = cache_key.clone() {
+        let lookup_url = url.to_string();
+        match tokio::task::spawn_blocking(move || provider::short_url::get_short_url(cache_key.as_str(), lookup_url.as_str())).await {
+            Ok(Ok(Some(short_url))) => {
+                metrics::record_shortener_call(ShortenerResult::Cached);
+                return Ok(short_url);
+            }
+            Ok(Ok(None)) => {}
+            Ok(Err(e)) => tracing::warn!("Failed to look up cached short URL: {}", e),
+            Err(e) => tracing::warn!("Failed to look up cached short URL: {}", e),
+        }
+    }
+
+    if BREAKER.is_open() {
+        metrics::record_shortener_call(ShortenerResult::Skipped);
+        return Err(anyhow::anyhow!("URL shortener is skipped after repeated failures"));
+    }
+
+    let shortener = current_shortener(&config);
+    let short_url = match shortener.shorten(url).await {
+        Ok(short_url) => {
+            BREAKER.record_success();
+            metrics::record_shortener_call(ShortenerResult::Shortened);
+            short_url
+        }
+        Err(e) => {
+            metrics::record_shortener_call(ShortenerResult::Failed);
+            if BREAKER.record_failure() {
+                tracing::warn!(
+                    "URL shortener failed {} times in a row, skipping it until the next check",
+                    FAILURE_THRESHOLD
+                );
+            }
+            return Err(e);
+        }
+    };
+
+    if let Some(cache_key) = cache_key {
+        let original_url = url.to_string();
+        let cached_short_url = short_url.clone();
+        match tokio::task::spawn_blocking(move || {
+            provider::short_url::save_short_url(cache_key.as_str(), original_url.as_str(), cached_short_url.as_str())
+        })
+            .await
+        {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::warn!("Failed to cache short URL: {}", e),
+            Err(e) => tracing::warn!("Failed to cache short URL: {}", e),
+        }
+    }
+    Ok(short_url)
+}
+
+/// Identifies the shortener settings that produced a cached short URL, so other settings don't reuse it.
+/// The built-in shortener keeps its own mapping, so its links aren't cached a second time.
+fn cache_key(config: &ShortenerConfig) -> Option<String> {
+    match (config.kind, &config.url) {
+        (ShortenerKind::External, Some(url)) => Some(format!(
+            "external {} {} {} {}",
+            url,
+            config.request_template.as_deref().unwrap_or_default(),
+            config.response_pointer,
+            config.result_format
+        )),
+        _ => None,
+    }
+}
+
+fn current_shortener(config: &Config) -> Arc<dyn Shortener> {
+    if let Some(shortener) = SHORTENER.read().ok().and_then(|shortener| shortener.clone()) {
+        return shortener;
+    }
+
+    let shortener: Arc<dyn Shortener> = Arc::from(from_config(&config.shortener, config.http.public_url.as_deref()));
+    if let Ok(mut current) = SHORTENER.write() {
+        *current = Some(shortener.clone());
+    }
+    shortener
+}
+
+/// Drops the current shortener so the next call builds one from the reloaded configuration.
+pub fn reset_shortener() {
+    if let Ok(mut current) = SHORTENER.write() {
+        *current = None;
+    }
+}
+
+/// Gives the shortener a fresh chance at the start of a fetch cycle.
+pub fn reset_circuit_breaker() {
+    BREAKER.reset();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cache_key;
+    use crate::config::{ShortenerConfig, ShortenerKind};
+
+    #[test]
+    fn cache_key_changes_with_the_shortener_settings() {
+        let external = ShortenerConfig {
+            kind: ShortenerKind::External,
+            url: Some("https://s.example.com/shorten".to_string()),
+            ..Default::default()
+        };
+        let moved = ShortenerConfig {
+            url: Some("https://short.example.org/api".to_string()),
+            ..external.clone()
+        };
+
+        assert!(cache_key(&external).is_some());
+        assert_ne!(cache_key(&external), cache_key(&moved));
+        assert_eq!(
+            cache_key(&ShortenerConfig {
+                kind: ShortenerKind::Builtin,
+                ..external
+            }),
+            None
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use crate::config::{LogFormat, LoggingConfig};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+/// Keeps the span exporter alive so buffered spans can be flushed before exiting.
+pub struct Telemetry {
+    tracer_provider: Option<SdkTracerProvider>,
+}
+
+impl Telemetry {
+    pub fn shutdown(self) {
+        if let Some(Err(e)) = self.tracer_provider.map(|provider| provider.shutdown()) {
+            eprintln!("Failed to flush exported spans: {}", e);
+        }
+    }
+}
+
+/// Installs the global subscriber: events in the configured format, filtered by `RUST_LOG`,
+/// plus OTLP span export when an endpoint is configured.
+pub fn init(config: &LoggingConfig) -> Telemetry {
+    let fmt_layer = match config.format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Pretty => tracing_subscriber::fmt::layer().pretty().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+    };
+
+    let tracer_provider = config.otlp_endpoint.as_deref().and_then(|endpoint| {
+        match build_tracer_provider(endpoint, config.service_name.as_str()) {
+            Ok(provider) => Some(provider),
+            Err(e) => {
+                eprintln!("Failed to set up OTLP span export to {}: {}", endpoint, e);
+                None
+            }
+        }
+    });
+    let otel_layer = tracer_provider
+        .as_ref()
+        .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME"))));
+
+    let subscriber = Registry::default()
+        .with(EnvFilter::from_default_env())
+        .with(fmt_layer)
+        .with(otel_layer);
+    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
+    tracing_log::LogTracer::init().expect("failed to init logger");
+
+    Telemetry { tracer_provider }
+}
+
+fn build_tracer_provider(endpoint: &str, service_name: &str) -> anyhow::Result<SdkTracerProvider> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(traces_endpoint(endpoint))
+        .build()?;
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name.to_string()).build())
+        .build())
+}
+
+/// Spans go to the traces path below the collector's base URL, like `OTEL_EXPORTER_OTLP_ENDPOINT` is meant.
+fn traces_endpoint(endpoint: &str) -> String {
+    format!("{}/v1/traces", endpoint.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::traces_endpoint;
+
+    #[test]
+    fn traces_endpoint_appends_signal_path() {
+        assert_eq!(traces_endpoint("http://collector:4318"), "http://collector:4318/v1/traces");
+        assert_eq!(traces_endpoint("http://collector:4318/"), "http://collector:4318/v1/traces");
+    }
+}